@@ -1,158 +1,507 @@
-
-declare i32 @echo(i8* , i32 ) 
-
-@int_to_string.result =  global [12 x i8] zeroinitializer, align 1
-
-define i8* @itos(i32  %0) {
-  %2 = alloca i32, align 4
-  %3 = alloca i32, align 4
-  %4 = alloca i32, align 4
-  %5 = alloca i32, align 4
-  %6 = alloca i32, align 4
-  %7 = alloca i8, align 1
-  store i32 %0, ptr %2, align 4
-  store i32 0, ptr %3, align 4
-  store i32 0, ptr %4, align 4
-  %8 = load i32, ptr %2, align 4
-  %9 = icmp slt i32 %8, 0
-  br i1 %9, label %10, label %13
-
-10:                                               ; preds = %1
-  store i32 1, ptr %3, align 4
-  %11 = load i32, ptr %2, align 4
-  %12 = sub nsw i32 0, %11
-  store i32 %12, ptr %2, align 4
-  br label %13
-
-13:                                               ; preds = %10, %1
-  %14 = load i32, ptr %2, align 4
-  %15 = icmp eq i32 %14, 0
-  br i1 %15, label %16, label %21
-
-16:                                               ; preds = %13
-  %17 = load i32, ptr %4, align 4
-  %18 = add nsw i32 %17, 1
-  store i32 %18, ptr %4, align 4
-  %19 = sext i32 %17 to i64
-  %20 = getelementptr inbounds [12 x i8], ptr @int_to_string.result, i64 0, i64 %19
-  store i8 48, ptr %20, align 1
-  br label %37
-
-21:                                               ; preds = %13
-  br label %22
-
-22:                                               ; preds = %25, %21
-  %23 = load i32, ptr %2, align 4
-  %24 = icmp sgt i32 %23, 0
-  br i1 %24, label %25, label %36
-
-25:                                               ; preds = %22
-  %26 = load i32, ptr %2, align 4
-  %27 = srem i32 %26, 10
-  %28 = add nsw i32 %27, 48
-  %29 = trunc i32 %28 to i8
-  %30 = load i32, ptr %4, align 4
-  %31 = add nsw i32 %30, 1
-  store i32 %31, ptr %4, align 4
-  %32 = sext i32 %30 to i64
-  %33 = getelementptr inbounds [12 x i8], ptr @int_to_string.result, i64 0, i64 %32
-  store i8 %29, ptr %33, align 1
-  %34 = load i32, ptr %2, align 4
-  %35 = sdiv i32 %34, 10
-  store i32 %35, ptr %2, align 4
-  br label %22
-
-36:                                               ; preds = %22
-  br label %37
-
-37:                                               ; preds = %36, %16
-  %38 = load i32, ptr %3, align 4
-  %39 = icmp ne i32 %38, 0
-  br i1 %39, label %40, label %45
-
-40:                                               ; preds = %37
-  %41 = load i32, ptr %4, align 4
-  %42 = add nsw i32 %41, 1
-  store i32 %42, ptr %4, align 4
-  %43 = sext i32 %41 to i64
-  %44 = getelementptr inbounds [12 x i8], ptr @int_to_string.result, i64 0, i64 %43
-  store i8 45, ptr %44, align 1
-  br label %45
-
-45:                                               ; preds = %40, %37
-  %46 = load i32, ptr %4, align 4
-  %47 = sext i32 %46 to i64
-  %48 = getelementptr inbounds [12 x i8], ptr @int_to_string.result, i64 0, i64 %47
-  store i8 0, ptr %48, align 1
-  store i32 0, ptr %5, align 4
-  %49 = load i32, ptr %4, align 4
-  %50 = sub nsw i32 %49, 1
-  store i32 %50, ptr %6, align 4
-  br label %51
-
-51:                                               ; preds = %55, %45
-  %52 = load i32, ptr %5, align 4
-  %53 = load i32, ptr %6, align 4
-  %54 = icmp slt i32 %52, %53
-  br i1 %54, label %55, label %75
-
-55:                                               ; preds = %51
-  %56 = load i32, ptr %5, align 4
-  %57 = sext i32 %56 to i64
-  %58 = getelementptr inbounds [12 x i8], ptr @int_to_string.result, i64 0, i64 %57
-  %59 = load i8, ptr %58, align 1
-  store i8 %59, ptr %7, align 1
-  %60 = load i32, ptr %6, align 4
-  %61 = sext i32 %60 to i64
-  %62 = getelementptr inbounds [12 x i8], ptr @int_to_string.result, i64 0, i64 %61
-  %63 = load i8, ptr %62, align 1
-  %64 = load i32, ptr %5, align 4
-  %65 = sext i32 %64 to i64
-  %66 = getelementptr inbounds [12 x i8], ptr @int_to_string.result, i64 0, i64 %65
-  store i8 %63, ptr %66, align 1
-  %67 = load i8, ptr %7, align 1
-  %68 = load i32, ptr %6, align 4
-  %69 = sext i32 %68 to i64
-  %70 = getelementptr inbounds [12 x i8], ptr @int_to_string.result, i64 0, i64 %69
-  store i8 %67, ptr %70, align 1
-  %71 = load i32, ptr %5, align 4
-  %72 = add nsw i32 %71, 1
-  store i32 %72, ptr %5, align 4
-  %73 = load i32, ptr %6, align 4
-  %74 = add nsw i32 %73, -1
-  store i32 %74, ptr %6, align 4
-  br label %51
-
-75:                                               ; preds = %51
-  %__res = load i8*, i8* @int_to_string.result
-  ret i8* %__res
-}
-
-define i32 @len(i8* %__input) {
-  %t = alloca i8*
-  store i8* %__input, i8* %t
-  %2 = alloca ptr
-  %3 = alloca i32, align 4
-  store i8* %t, ptr %2
-  store i32 0, ptr %3, align 4
-  br label %4
-
-4:                                                ; preds = %11, %1
-  %5 = load ptr, ptr %2, align 8
-  %6 = load i32, ptr %3, align 4
-  %7 = sext i32 %6 to i64
-  %8 = getelementptr inbounds i8, ptr %5, i64 %7
-  %9 = load i8, ptr %8, align 1
-  %10 = icmp ne i8 %9, 0
-  br i1 %10, label %11, label %14
-
-11:                                               ; preds = %4
-  %12 = load i32, ptr %3, align 4
-  %13 = add nsw i32 %12, 1
-  store i32 %13, ptr %3, align 4
-  br label %4
-
-14:                                               ; preds = %4
-  %15 = load i32, ptr %3, align 4
-  ret i32 %15
-}
+%str = type { ptr, i32 }
+%arr = type { ptr, i32 }
+
+declare void @ika_echo(%str)
+declare ptr @ika_alloc(i64)
+declare i32 @ika_read(ptr, i32)
+declare void @ika_exit(i32)
+declare void @llvm.memcpy.p0.p0.i64(ptr, ptr, i64, i1)
+
+define %str @ika_itos(i32 %n) {
+entry:
+  %buf = call ptr @ika_alloc(i64 12)
+  %neg = icmp slt i32 %n, 0
+  %wide = sext i32 %n to i64
+  %minus = sub i64 0, %wide
+  %abs = select i1 %neg, i64 %minus, i64 %wide
+  br label %digits
+
+; digits are written from the end of the buffer towards the front
+digits:
+  %v = phi i64 [ %abs, %entry ], [ %q, %digits ]
+  %pos = phi i32 [ 12, %entry ], [ %next, %digits ]
+  %q = udiv i64 %v, 10
+  %r = urem i64 %v, 10
+  %r8 = trunc i64 %r to i8
+  %c = add i8 %r8, 48
+  %next = sub i32 %pos, 1
+  %idx = sext i32 %next to i64
+  %slot = getelementptr i8, ptr %buf, i64 %idx
+  store i8 %c, ptr %slot
+  %more = icmp ne i64 %q, 0
+  br i1 %more, label %digits, label %sign
+
+sign:
+  br i1 %neg, label %negative, label %done
+
+negative:
+  %mpos = sub i32 %next, 1
+  %midx = sext i32 %mpos to i64
+  %mslot = getelementptr i8, ptr %buf, i64 %midx
+  store i8 45, ptr %mslot
+  br label %done
+
+done:
+  %start = phi i32 [ %next, %sign ], [ %mpos, %negative ]
+  %sidx = sext i32 %start to i64
+  %ptr = getelementptr i8, ptr %buf, i64 %sidx
+  %len = sub i32 12, %start
+  %s0 = insertvalue %str undef, ptr %ptr, 0
+  %s1 = insertvalue %str %s0, i32 %len, 1
+  ret %str %s1
+}
+
+@true_str = private constant [4 x i8] c"true"
+@false_str = private constant [5 x i8] c"false"
+
+define %str @ika_btos(i1 %b) {
+entry:
+  %s = select i1 %b, %str { ptr @true_str, i32 4 }, %str { ptr @false_str, i32 5 }
+  ret %str %s
+}
+
+define %str @ika_ctos(i8 %c) {
+entry:
+  %buf = call ptr @ika_alloc(i64 1)
+  store i8 %c, ptr %buf
+  %s0 = insertvalue %str undef, ptr %buf, 0
+  %s1 = insertvalue %str %s0, i32 1, 1
+  ret %str %s1
+}
+
+; fixed notation with up to six fractional digits, trailing zeros trimmed
+define %str @ika_ftos(double %x) {
+entry:
+  %buf = call ptr @ika_alloc(i64 40)
+  %neg = fcmp olt double %x, 0.000000e+00
+  %nx = fneg double %x
+  %abs = select i1 %neg, double %nx, double %x
+  %ip = fptoui double %abs to i64
+  %ipf = uitofp i64 %ip to double
+  %frac = fsub double %abs, %ipf
+  %scaled = fmul double %frac, 1.000000e+06
+  %rounded = fadd double %scaled, 5.000000e-01
+  %fd0 = fptoui double %rounded to i64
+  %carry = icmp uge i64 %fd0, 1000000
+  %ip1 = add i64 %ip, 1
+  %ipc = select i1 %carry, i64 %ip1, i64 %ip
+  %fd = select i1 %carry, i64 0, i64 %fd0
+  br label %trim
+
+trim:
+  %t = phi i64 [ %fd, %entry ], [ %tq, %trim_step ]
+  %count = phi i32 [ 6, %entry ], [ %cm, %trim_step ]
+  %tr = urem i64 %t, 10
+  %zero = icmp eq i64 %tr, 0
+  %many = icmp ugt i32 %count, 1
+  %drop = and i1 %zero, %many
+  br i1 %drop, label %trim_step, label %frac_digits
+
+trim_step:
+  %tq = udiv i64 %t, 10
+  %cm = sub i32 %count, 1
+  br label %trim
+
+frac_digits:
+  %fv = phi i64 [ %t, %trim ], [ %fq, %frac_digits ]
+  %fpos = phi i32 [ 40, %trim ], [ %fnext, %frac_digits ]
+  %fleft = phi i32 [ %count, %trim ], [ %fleft1, %frac_digits ]
+  %fq = udiv i64 %fv, 10
+  %fr = urem i64 %fv, 10
+  %fr8 = trunc i64 %fr to i8
+  %fc = add i8 %fr8, 48
+  %fnext = sub i32 %fpos, 1
+  %fidx = sext i32 %fnext to i64
+  %fslot = getelementptr i8, ptr %buf, i64 %fidx
+  store i8 %fc, ptr %fslot
+  %fleft1 = sub i32 %fleft, 1
+  %fmore = icmp ne i32 %fleft1, 0
+  br i1 %fmore, label %frac_digits, label %point
+
+point:
+  %ppos = sub i32 %fnext, 1
+  %pidx = sext i32 %ppos to i64
+  %pslot = getelementptr i8, ptr %buf, i64 %pidx
+  store i8 46, ptr %pslot
+  br label %int_digits
+
+int_digits:
+  %iv = phi i64 [ %ipc, %point ], [ %iq, %int_digits ]
+  %ipos = phi i32 [ %ppos, %point ], [ %inext, %int_digits ]
+  %iq = udiv i64 %iv, 10
+  %ir = urem i64 %iv, 10
+  %ir8 = trunc i64 %ir to i8
+  %ic = add i8 %ir8, 48
+  %inext = sub i32 %ipos, 1
+  %iidx = sext i32 %inext to i64
+  %islot = getelementptr i8, ptr %buf, i64 %iidx
+  store i8 %ic, ptr %islot
+  %imore = icmp ne i64 %iq, 0
+  br i1 %imore, label %int_digits, label %sign
+
+sign:
+  br i1 %neg, label %negative, label %done
+
+negative:
+  %mpos = sub i32 %inext, 1
+  %midx = sext i32 %mpos to i64
+  %mslot = getelementptr i8, ptr %buf, i64 %midx
+  store i8 45, ptr %mslot
+  br label %done
+
+done:
+  %start = phi i32 [ %inext, %sign ], [ %mpos, %negative ]
+  %sidx = sext i32 %start to i64
+  %ptr = getelementptr i8, ptr %buf, i64 %sidx
+  %len = sub i32 40, %start
+  %s0 = insertvalue %str undef, ptr %ptr, 0
+  %s1 = insertvalue %str %s0, i32 %len, 1
+  ret %str %s1
+}
+
+define i32 @ika_len(%str %s) {
+entry:
+  %len = extractvalue %str %s, 1
+  ret i32 %len
+}
+
+define %str @ika_str_concat(%str %a, %str %b) {
+entry:
+  %ap = extractvalue %str %a, 0
+  %al = extractvalue %str %a, 1
+  %bp = extractvalue %str %b, 0
+  %bl = extractvalue %str %b, 1
+  %len = add i32 %al, %bl
+  %alx = zext i32 %al to i64
+  %blx = zext i32 %bl to i64
+  %lenx = zext i32 %len to i64
+  %buf = call ptr @ika_alloc(i64 %lenx)
+  call void @llvm.memcpy.p0.p0.i64(ptr %buf, ptr %ap, i64 %alx, i1 false)
+  %tail = getelementptr i8, ptr %buf, i64 %alx
+  call void @llvm.memcpy.p0.p0.i64(ptr %tail, ptr %bp, i64 %blx, i1 false)
+  %s0 = insertvalue %str undef, ptr %buf, 0
+  %s1 = insertvalue %str %s0, i32 %len, 1
+  ret %str %s1
+}
+
+; lexicographic byte comparison: -1, 0 or 1
+define i32 @ika_str_cmp(%str %a, %str %b) {
+entry:
+  %ap = extractvalue %str %a, 0
+  %al = extractvalue %str %a, 1
+  %bp = extractvalue %str %b, 0
+  %bl = extractvalue %str %b, 1
+  %a_shorter = icmp ult i32 %al, %bl
+  %n = select i1 %a_shorter, i32 %al, i32 %bl
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i1, %next ]
+  %end = icmp eq i32 %i, %n
+  br i1 %end, label %tail, label %body
+
+body:
+  %ix = zext i32 %i to i64
+  %pa = getelementptr i8, ptr %ap, i64 %ix
+  %ca = load i8, ptr %pa
+  %pb = getelementptr i8, ptr %bp, i64 %ix
+  %cb = load i8, ptr %pb
+  %same = icmp eq i8 %ca, %cb
+  br i1 %same, label %next, label %diff
+
+next:
+  %i1 = add i32 %i, 1
+  br label %loop
+
+diff:
+  %lt = icmp ult i8 %ca, %cb
+  %r = select i1 %lt, i32 -1, i32 1
+  ret i32 %r
+
+tail:
+  %gt = icmp ugt i32 %al, %bl
+  %r1 = select i1 %gt, i32 1, i32 0
+  %r2 = select i1 %a_shorter, i32 -1, i32 %r1
+  ret i32 %r2
+}
+
+; bounds are clamped to [0, len] and `to` is never below `from`
+define %str @ika_str_slice(%str %s, i32 %from, i32 %to) {
+entry:
+  %p = extractvalue %str %s, 0
+  %l = extractvalue %str %s, 1
+  %to_neg = icmp slt i32 %to, 0
+  %to0 = select i1 %to_neg, i32 0, i32 %to
+  %to_big = icmp sgt i32 %to0, %l
+  %to1 = select i1 %to_big, i32 %l, i32 %to0
+  %from_neg = icmp slt i32 %from, 0
+  %from0 = select i1 %from_neg, i32 0, i32 %from
+  %from_big = icmp sgt i32 %from0, %to1
+  %from1 = select i1 %from_big, i32 %to1, i32 %from0
+  %fx = sext i32 %from1 to i64
+  %ptr = getelementptr i8, ptr %p, i64 %fx
+  %len = sub i32 %to1, %from1
+  %s0 = insertvalue %str undef, ptr %ptr, 0
+  %s1 = insertvalue %str %s0, i32 %len, 1
+  ret %str %s1
+}
+
+define void @ika_print_str(%str %s) {
+entry:
+  call void @ika_echo(%str %s)
+  ret void
+}
+
+define void @ika_print_i32(i32 %n) {
+entry:
+  %s = call %str @ika_itos(i32 %n)
+  call void @ika_echo(%str %s)
+  ret void
+}
+
+define void @ika_print_bool(i1 %b) {
+entry:
+  %s = call %str @ika_btos(i1 %b)
+  call void @ika_echo(%str %s)
+  ret void
+}
+
+define void @ika_print_char(i8 %c) {
+entry:
+  %s = call %str @ika_ctos(i8 %c)
+  call void @ika_echo(%str %s)
+  ret void
+}
+
+define void @ika_print_f64(double %x) {
+entry:
+  %s = call %str @ika_ftos(double %x)
+  call void @ika_echo(%str %s)
+  ret void
+}
+
+@newline_str = private constant [1 x i8] c"\0A"
+
+define void @ika_print_newline() {
+entry:
+  call void @ika_echo(%str { ptr @newline_str, i32 1 })
+  ret void
+}
+
+; stdin is read through one shared buffer
+@stdin_buf = private global [4096 x i8] zeroinitializer
+@stdin_pos = private global i32 0
+@stdin_len = private global i32 0
+
+; next byte of stdin without consuming it, -1 at end of input
+define private i32 @stdin_peek() {
+entry:
+  %pos = load i32, ptr @stdin_pos
+  %len = load i32, ptr @stdin_len
+  %empty = icmp sge i32 %pos, %len
+  br i1 %empty, label %refill, label %ready
+
+refill:
+  %n = call i32 @ika_read(ptr @stdin_buf, i32 4096)
+  store i32 0, ptr @stdin_pos
+  %eof = icmp sle i32 %n, 0
+  %filled = select i1 %eof, i32 0, i32 %n
+  store i32 %filled, ptr @stdin_len
+  br i1 %eof, label %end, label %ready
+
+ready:
+  %p = load i32, ptr @stdin_pos
+  %pidx = sext i32 %p to i64
+  %slot = getelementptr [4096 x i8], ptr @stdin_buf, i64 0, i64 %pidx
+  %c = load i8, ptr %slot
+  %cz = zext i8 %c to i32
+  ret i32 %cz
+
+end:
+  ret i32 -1
+}
+
+define private i32 @stdin_next() {
+entry:
+  %c = call i32 @stdin_peek()
+  %eof = icmp eq i32 %c, -1
+  br i1 %eof, label %done, label %advance
+
+advance:
+  %pos = load i32, ptr @stdin_pos
+  %pos1 = add i32 %pos, 1
+  store i32 %pos1, ptr @stdin_pos
+  br label %done
+
+done:
+  ret i32 %c
+}
+
+; one line without its line ending, empty at end of input
+define %str @ika_read_line() {
+entry:
+  %buf0 = call ptr @ika_alloc(i64 64)
+  br label %loop
+
+loop:
+  %buf = phi ptr [ %buf0, %entry ], [ %buf2, %store ]
+  %cap = phi i32 [ 64, %entry ], [ %cap2, %store ]
+  %len = phi i32 [ 0, %entry ], [ %len1, %store ]
+  %c = call i32 @stdin_next()
+  %eof = icmp eq i32 %c, -1
+  %nl = icmp eq i32 %c, 10
+  %stop = or i1 %eof, %nl
+  br i1 %stop, label %done, label %check
+
+check:
+  %full = icmp eq i32 %len, %cap
+  br i1 %full, label %grow, label %store
+
+grow:
+  %newcap = mul i32 %cap, 2
+  %newcapx = zext i32 %newcap to i64
+  %newbuf = call ptr @ika_alloc(i64 %newcapx)
+  %lenx = zext i32 %len to i64
+  call void @llvm.memcpy.p0.p0.i64(ptr %newbuf, ptr %buf, i64 %lenx, i1 false)
+  br label %store
+
+store:
+  %buf2 = phi ptr [ %buf, %check ], [ %newbuf, %grow ]
+  %cap2 = phi i32 [ %cap, %check ], [ %newcap, %grow ]
+  %idx = zext i32 %len to i64
+  %slot = getelementptr i8, ptr %buf2, i64 %idx
+  %c8 = trunc i32 %c to i8
+  store i8 %c8, ptr %slot
+  %len1 = add i32 %len, 1
+  br label %loop
+
+done:
+  %nonempty = icmp ugt i32 %len, 0
+  br i1 %nonempty, label %check_cr, label %finish
+
+check_cr:
+  %last = sub i32 %len, 1
+  %lastx = zext i32 %last to i64
+  %lastslot = getelementptr i8, ptr %buf, i64 %lastx
+  %lastc = load i8, ptr %lastslot
+  %cr = icmp eq i8 %lastc, 13
+  %trimmed = select i1 %cr, i32 %last, i32 %len
+  br label %finish
+
+finish:
+  %flen = phi i32 [ %len, %done ], [ %trimmed, %check_cr ]
+  %s0 = insertvalue %str undef, ptr %buf, 0
+  %s1 = insertvalue %str %s0, i32 %flen, 1
+  ret %str %s1
+}
+
+; skips leading whitespace and reads an optionally signed decimal number,
+; 0 if there is none
+define i32 @ika_read_int() {
+entry:
+  br label %skip
+
+skip:
+  %c = call i32 @stdin_peek()
+  %space = icmp eq i32 %c, 32
+  %tab = icmp eq i32 %c, 9
+  %nl = icmp eq i32 %c, 10
+  %cr = icmp eq i32 %c, 13
+  %ws0 = or i1 %space, %tab
+  %ws1 = or i1 %nl, %cr
+  %ws = or i1 %ws0, %ws1
+  br i1 %ws, label %skip_one, label %sign
+
+skip_one:
+  call i32 @stdin_next()
+  br label %skip
+
+sign:
+  %minus = icmp eq i32 %c, 45
+  br i1 %minus, label %eat_sign, label %digits
+
+eat_sign:
+  call i32 @stdin_next()
+  br label %digits
+
+digits:
+  %v = phi i32 [ 0, %sign ], [ 0, %eat_sign ], [ %v1, %digit ]
+  %d = call i32 @stdin_peek()
+  %ge0 = icmp sge i32 %d, 48
+  %le9 = icmp sle i32 %d, 57
+  %isdigit = and i1 %ge0, %le9
+  br i1 %isdigit, label %digit, label %done
+
+digit:
+  call i32 @stdin_next()
+  %v10 = mul i32 %v, 10
+  %dv = sub i32 %d, 48
+  %v1 = add i32 %v10, %dv
+  br label %digits
+
+done:
+  %neg = sub i32 0, %v
+  %r = select i1 %minus, i32 %neg, i32 %v
+  ret i32 %r
+}
+
+; argv as a str[], the strings point into argv itself
+define %arr @ika_args(i32 %argc, ptr %argv) {
+entry:
+  %n = zext i32 %argc to i64
+  %bytes = mul i64 %n, 16
+  %items = call ptr @ika_alloc(i64 %bytes)
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i1, %store ]
+  %end = icmp eq i32 %i, %argc
+  br i1 %end, label %done, label %arg
+
+arg:
+  %ix = zext i32 %i to i64
+  %pp = getelementptr ptr, ptr %argv, i64 %ix
+  %p = load ptr, ptr %pp
+  br label %strlen
+
+strlen:
+  %l = phi i32 [ 0, %arg ], [ %l1, %strlen_next ]
+  %lx = zext i32 %l to i64
+  %cp = getelementptr i8, ptr %p, i64 %lx
+  %c = load i8, ptr %cp
+  %nul = icmp eq i8 %c, 0
+  br i1 %nul, label %store, label %strlen_next
+
+strlen_next:
+  %l1 = add i32 %l, 1
+  br label %strlen
+
+store:
+  %s0 = insertvalue %str undef, ptr %p, 0
+  %s1 = insertvalue %str %s0, i32 %l, 1
+  %slot = getelementptr %str, ptr %items, i64 %ix
+  store %str %s1, ptr %slot
+  %i1 = add i32 %i, 1
+  br label %loop
+
+done:
+  %a0 = insertvalue %arr undef, ptr %items, 0
+  %a1 = insertvalue %arr %a0, i32 %argc, 1
+  ret %arr %a1
+}
+
+@bounds_msg = private constant [20 x i8] c"index out of bounds\0A"
+
+define void @ika_bounds_check(i32 %idx, i32 %len) {
+entry:
+  %ok = icmp ult i32 %idx, %len
+  br i1 %ok, label %fine, label %fail
+
+fine:
+  ret void
+
+fail:
+  call void @ika_echo(%str { ptr @bounds_msg, i32 20 })
+  call void @ika_exit(i32 101)
+  unreachable
+}
@@ -0,0 +1,58 @@
+%str = type { ptr, i32 }
+
+declare i64 @write(i32, ptr, i64)
+declare i64 @read(i32, ptr, i64)
+declare ptr @malloc(i64)
+declare void @exit(i32) noreturn
+
+@stdout_fd = private constant i32 1
+@stdin_fd = private constant i32 0
+
+; write may return after a partial write, so keep going until the
+; whole string is out or the descriptor reports an error
+define void @ika_echo(%str %s) {
+entry:
+    %ptr = extractvalue %str %s, 0
+    %len = extractvalue %str %s, 1
+    %total = zext i32 %len to i64
+    %fd = load i32, ptr @stdout_fd
+    br label %loop
+
+loop:
+    %done = phi i64 [ 0, %entry ], [ %next, %more ]
+    %left = sub i64 %total, %done
+    %empty = icmp sle i64 %left, 0
+    br i1 %empty, label %exit, label %more
+
+more:
+    %cur = getelementptr i8, ptr %ptr, i64 %done
+    %n = call i64 @write(i32 %fd, ptr %cur, i64 %left)
+    %failed = icmp sle i64 %n, 0
+    %next = add i64 %done, %n
+    br i1 %failed, label %exit, label %loop
+
+exit:
+    ret void
+}
+
+define ptr @ika_alloc(i64 %size) {
+entry:
+    %mem = call ptr @malloc(i64 %size)
+    ret ptr %mem
+}
+
+; bytes read into %buf, 0 at end of input and -1 on failure
+define i32 @ika_read(ptr %buf, i32 %cap) {
+entry:
+    %fd = load i32, ptr @stdin_fd
+    %size = zext i32 %cap to i64
+    %n = call i64 @read(i32 %fd, ptr %buf, i64 %size)
+    %res = trunc i64 %n to i32
+    ret i32 %res
+}
+
+define void @ika_exit(i32 %code) {
+entry:
+    call void @exit(i32 %code)
+    unreachable
+}
@@ -1,40 +1,59 @@
-; declare i32 @write(i32, i8*, i32) nounwind
-
-; define i32 @echo(i8* %__str, i32 %__len) {
-; entry:
-;     %__str_1 = alloca i8*
-;     store i8* %__str , i8* %__str_1
-;     %__stdout_fd = alloca i32, align 4
-;     store i32 1, i32* %__stdout_fd, align 4
-;     call i32 @write(i32 1, i8* %__str_1, i32 %__len)
-;     ret i32 0
-; }
-
-declare i32 @WriteConsoleA(i32, i8*, i32, i32*, i8*) 
-declare i32 @GetStdHandle(i32) 
-; 常量：STD_OUTPUT_HANDLE
-
-
-define i32 @echo(i8* %__str, i32 %__len) {
-entry:
-    ; 分配局部变量
-    %__str_1 = alloca i8*
-    store i8* %__str, i8* %__str_1
-
-    ; 获取标准输出句柄 (stdout)
-    %stdout_handle = alloca i32
-    store i32 -11, i32* %stdout_handle
-    %stdout = load i32, i32* %stdout_handle
-    ; 分配一个变量存储写入的字节数
-    %bytes_written = alloca i32, align 4
-    store i32 0, i32* %bytes_written, align 4
-
-    %hConsole = call i32 @GetStdHandle(i32 %stdout)
-
-
-    ; 调用 WriteFile 输出数据到标准输出
-    call i32 @WriteConsoleA(i32 %hConsole, i8* %__str_1, i32 %__len, i32* %bytes_written, i32 0)
-
-    ; 返回 0，表示函数执行成功
-    ret i32 0
-}
+%str = type { ptr, i32 }
+
+declare ptr @GetStdHandle(i32)
+declare i32 @WriteConsoleA(ptr, ptr, i32, ptr, ptr)
+declare i32 @ReadFile(ptr, ptr, i32, ptr, ptr)
+declare ptr @GetProcessHeap()
+declare ptr @HeapAlloc(ptr, i32, i64)
+declare void @ExitProcess(i32) noreturn
+
+; STD_OUTPUT_HANDLE
+@std_output_handle = private constant i32 -11
+; STD_INPUT_HANDLE
+@std_input_handle = private constant i32 -10
+
+define void @ika_echo(%str %s) {
+entry:
+    %ptr = extractvalue %str %s, 0
+    %len = extractvalue %str %s, 1
+
+    ; number of bytes written
+    %bytes_written = alloca i32, align 4
+    store i32 0, ptr %bytes_written, align 4
+
+    %stdout = load i32, ptr @std_output_handle
+    %hConsole = call ptr @GetStdHandle(i32 %stdout)
+
+    call i32 @WriteConsoleA(ptr %hConsole, ptr %ptr, i32 %len, ptr %bytes_written, ptr null)
+
+    ret void
+}
+
+define ptr @ika_alloc(i64 %size) {
+entry:
+    %heap = call ptr @GetProcessHeap()
+    %mem = call ptr @HeapAlloc(ptr %heap, i32 0, i64 %size)
+    ret ptr %mem
+}
+
+; bytes read into %buf, 0 at end of input and -1 on failure
+define i32 @ika_read(ptr %buf, i32 %cap) {
+entry:
+    %bytes_read = alloca i32, align 4
+    store i32 0, ptr %bytes_read, align 4
+
+    %stdin = load i32, ptr @std_input_handle
+    %hInput = call ptr @GetStdHandle(i32 %stdin)
+
+    %ok = call i32 @ReadFile(ptr %hInput, ptr %buf, i32 %cap, ptr %bytes_read, ptr null)
+    %failed = icmp eq i32 %ok, 0
+    %n = load i32, ptr %bytes_read, align 4
+    %res = select i1 %failed, i32 -1, i32 %n
+    ret i32 %res
+}
+
+define void @ika_exit(i32 %code) {
+entry:
+    call void @ExitProcess(i32 %code)
+    unreachable
+}
@@ -1,622 +1,748 @@
-use crate::parser::{ASTNode};
-use crate::lexer::{TokenType};
-use crate::sema::Function;
-use crate::SrcInfo;
-use core::net;
-use std::collections::HashMap;
-use std::vec;
-use crate::codegen_lib::{generate_lib};
-pub struct Codegen {
-    output: String,
-    tmp: i64,
-    scope: usize,
-    pub sym_table: Vec<HashMap<String, Info>>, 
-}
-
-#[derive(Debug, Clone)]
-pub enum Info{
-    Variable{
-        tmp_name:String,
-        ty:String,
-        scope: usize,
-        size: i64,
-    },
-    Function{
-        tmp_name:String,
-        ret_ty: String,
-        paras: Vec<String>,
-        scope:usize,
-    }
-}
-
-
-
-impl Codegen {
-    pub fn new() -> Self {
-        Codegen {
-            output: String::new(),
-            tmp: 0,
-            scope: 1,
-            sym_table: Vec::new(),
-        }
-    }
-
-    fn new_tmp(&mut self) -> i64{
-        let tmp = self.tmp;
-        self.tmp += 1;
-        tmp
-    }
-
-    fn add_to_symbol(&mut self, scope:usize, name:String, info:Info){
-        
-        let t = &mut self.sym_table;
-        let a = t.get_mut(scope).unwrap();
-        a.insert(name, info);
-    }
-
-    fn get_funinfo(&self, name: String) -> Option<(String, String, Vec<String>, usize)>{
-        let fun =self.sym_table.get(1).clone().unwrap().get(&name).unwrap().clone();
-        match fun {
-            Info::Function { tmp_name, ret_ty, paras, scope } 
-                => Some((tmp_name, ret_ty, paras, scope)),
-            _ => None
-        }
-    }
-
-    fn get_varinfo(&self, name:String)->Option<(String, String, usize, i64)>{
-        let mut s =self.scope;
-        while s >= 1{
-            let a = self.sym_table.get(s ).clone().unwrap();
-            let v = a.get(&name).cloned();
-            if v.is_none(){
-                s -= 1;
-            }else{
-                break;
-            }
-        }
-
-        if s < 1{
-            None
-        }else{
-            let var =self.sym_table.get(s).clone().unwrap().get(&name).cloned().unwrap().clone();
-            match var {
-                Info::Variable { tmp_name, ty, scope, size }
-                   => Some((tmp_name, ty, scope, size)),
-                _ => None
-            }
-        } 
-    }
-
-    pub fn generate_code(&mut self, ast:ASTNode, info:SrcInfo) -> &String{
-
-        self.sym_table.push(HashMap::new());
-        self.sym_table.push(HashMap::new());
-        self.sym_table.push(HashMap::new());
-        self.sym_table.push(HashMap::new());
-        self.sym_table.push(HashMap::new());
-
-        self.generate_program(ast, info);
-        &self.output 
-    }
-
-    pub fn generate_program(&mut self, ast:ASTNode, info:SrcInfo) {
-        
-        let tt =info.target_triple;
-        self.output.push_str(&format!("target triple = \"{tt}\"\n"));
-        
-        let v = &generate_lib();
-        for i in v{
-            self.output.push_str(i);
-        }
-        
-        self.add_to_symbol(1, "echo".to_string(), Info::Function { tmp_name: "echo".to_string(), ret_ty: "i32".to_string(), paras:Vec::from(["i8*".to_string(),"i32".to_string()]), scope: 1 });
-        self.add_to_symbol(1, "itos".to_string(), Info::Function { tmp_name: "itos".to_string(), ret_ty: "i8*".to_string(), paras:Vec::from(["i32".to_string()]), scope: 1 });
-        self.add_to_symbol(1, "len".to_string(), Info::Function { tmp_name: "len".to_string(), ret_ty: "i32".to_string(), paras:Vec::from(["i8*".to_string()]), scope: 1 });
-        if let ASTNode::Program(vec) = ast{
-            for stat in vec{
-                self.generate_statement(stat);
-            }
-            
-        }
-    }
-
-    pub fn generate_statement(&mut self, stat:ASTNode){
-        match stat{
-            ASTNode::VariableDefinition { 
-                var_type, 
-                identifier, 
-                var_value 
-            } => self.generate_code_vardef(var_type, identifier, var_value),
-
-            ASTNode::Assignment { 
-                identifier, 
-                var_value 
-            } => self.generate_code_assignment(identifier, var_value),
-
-            ASTNode::FunctionDefinition { 
-                fn_name, 
-                parameters, 
-                ret_type, 
-                body 
-            } => self.generate_code_fundef(fn_name, parameters, ret_type, body),
-
-            ASTNode::Return(ast) => self.generate_code_return(*ast),
-
-            ASTNode::IfElse { condition, if_body, elif_body, el_condition, else_body 
-            } => self.generate_code_ifelse(condition, if_body, elif_body,el_condition, else_body),
-
-            ASTNode::While { condition, body } => self.generate_code_while( condition, body),
-            
-            ASTNode::FunctionCall { fn_name, argument } => {self.generate_code_funcall(fn_name, argument);()},
-            _ => ()
-        }
-    }
-
-    pub fn generate_code_vardef(&mut self, var_type:String, identifier:String, var_value:Option<Box<ASTNode>>){
-        
-        let llvm_var_type = turn_to_llvm_type(var_type).unwrap();
-
-        if self.scope != 1{
-            let tmp = self.tmp;
-            self.tmp += 1;
-            self.output.push_str(&format!("\t%{tmp} = alloca {llvm_var_type}\n"));
-           
-            match var_value{
-                Some(expr) => {
-                    let value = self.generate_code_expression(*expr);
-                    // if llvm_var_type != "i8*"{
-                        let tmp2 = self.tmp;
-                        self.tmp += 1;
-                        self.output.push_str(&format!("\t%{tmp2} = load {llvm_var_type}, {llvm_var_type}* {value}\n"));
-                        self.output.push_str(&format!("\tstore {llvm_var_type} %{tmp2}, {llvm_var_type}* %{tmp}\n"));
-                    // }
-                    // else{}
-                }
-                None => ()
-            }
-            let varinfo = Info::Variable { tmp_name: format!("%{tmp}"), ty:llvm_var_type, scope: self.scope, size: 32 };
-            self.add_to_symbol(self.scope, identifier, varinfo);
-        } else{
-            let tmp = self.tmp;
-            self.tmp += 1;
-            if llvm_var_type != "i8*"{
-                self.output.push_str(&format!("@{tmp} = global {llvm_var_type}"));
-            } else{
-                self.output.push_str(&format!("@{tmp} = global "));
-            }
-           
-           
-            match var_value{
-                Some(expr) => {
-                    let value = self.generate_code_expression(*expr);
-                    self.output.push_str(&format!(" {value}\n"));
-                }
-                None => {
-                    self.output.push_str(&format!(" 0\n"));
-                }
-            }
-            let varinfo = Info::Variable { tmp_name: format!("@{tmp}"), ty:llvm_var_type, scope: 1, size: 32 };
-            self.add_to_symbol(self.scope, identifier, varinfo);
-
-        }
-
-    }
-
-    fn generate_code_expression(&mut self, ast:ASTNode) -> String{
-        match ast{
-            ASTNode::InfixExpression { 
-                left_expr, 
-                op, 
-                right_expr 
-            } => {
-
-
-                let left = self.generate_code_expression(*left_expr);
-                
-
-                let right = self.generate_code_expression(*right_expr);
-
-                if self.scope != 1{
-
-                    let tmp_left = self.tmp;
-                    self.tmp += 1;
-                    self.output.push_str(format!("\t%{tmp_left} = load i32, i32* {left}\n").as_str());
-
-                    let tmp_right = self.tmp;
-                    self.tmp += 1;
-                    self.output.push_str(format!("\t%{tmp_right} = load i32, i32* {right}\n").as_str());
-
-                    let tmp_res = self.tmp;
-                    self.tmp += 1;
-                    let mut ty = String::new();
-                    if op == "+"{
-                        self.output.push_str(format!("\t%{tmp_res} = add i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i32".to_string();
-                    } else if op == "-"{
-                        self.output.push_str(format!("\t%{tmp_res} = sub i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i32".to_string();
-                    }else if op == "*"{
-                        self.output.push_str(format!("\t%{tmp_res} = mul i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i32".to_string();
-                    }else if op == "/"{
-                        self.output.push_str(format!("\t%{tmp_res} = udiv i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i32".to_string();
-                    }else if op == "%"{
-                        self.output.push_str(format!("\t%{tmp_res} = srem i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i32".to_string();
-                    }else if op == "=="{
-                        self.output.push_str(format!("\t%{tmp_res} = icmp eq i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i1".to_string();
-                    } else if op == ">="{
-                        self.output.push_str(format!("\t%{tmp_res} = icmp sge i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i1".to_string();
-                    }else if op == "<="{
-                        self.output.push_str(format!("\t%{tmp_res} = icmp sle i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i1".to_string();
-                    }else if op == ">"{
-                        self.output.push_str(format!("\t%{tmp_res} = icmp sgt i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i1".to_string();
-                    }else if op == "<"{
-                        self.output.push_str(format!("\t%{tmp_res} = icmp slt i32 %{tmp_left}, %{tmp_right}\n").as_str());
-                        ty = "i1".to_string();
-                    }else if op == "|"{
-                        self.output.push_str(format!("\t%{tmp_res} = or i1 %{tmp_left}, i1 %{tmp_right}\n").as_str());
-                        ty = "i1".to_string();
-                    }else if op == "&"{
-                        self.output.push_str(format!("\t%{tmp_res} = and i1 %{tmp_left},i1 %{tmp_right}\n").as_str());
-                        ty = "i1".to_string();
-                    }else if op == "^"{
-                        self.output.push_str(format!("\t%{tmp_res} = xor i1 %{tmp_left},i1 %{tmp_right}\n").as_str());
-                        ty = "i1".to_string();
-                    }else if op == "!"{
-                        self.output.push_str(format!("\t%{tmp_res} = xor i1 %{tmp_left}, true\n").as_str());
-                        ty = "i1".to_string();
-                    }else{
-
-                    }
-
-                    let tmp_new = self.tmp;
-                    self.tmp += 1;
-                    self.output.push_str(format!("\t%{tmp_new} = alloca {ty}\n").as_str());
-                    self.output.push_str(format!("\tstore {ty} %{tmp_res}, ptr %{tmp_new}\n").as_str());
-                    format!("%{tmp_new}")
-                } else{
-                    let tmp_new = if op == "+"{
-                        turn_string_to_int(left) + turn_string_to_int(right)
-                    } else if op == "-"{
-                        turn_string_to_int(left) - turn_string_to_int(right)
-                    }else if op == "*"{
-                        turn_string_to_int(left) * turn_string_to_int(right)
-                    }else if op == "/"{
-                        turn_string_to_int(left) / turn_string_to_int(right)
-                    }else{
-                        0
-                    };
-                    format!("{tmp_new}")
-                }
-            },
-            ASTNode::Number(num) => {
-                
-                if self.scope != 1{
-                    let tmp = self.tmp;
-                    self.tmp += 1;
-                    self.output.push_str(&format!("\t%{tmp} = alloca i32\n"));
-                    self.output.push_str(&format!("\tstore i32 {num}, i32* %{tmp}\n"));
-                    format!("%{tmp}")
-                } else{
-                    format!("{num}")
-
-                }
- 
-            },
-            ASTNode::String(value)=>{
-                let len = value.len() + 1;
-                if self.scope != 1{
-                    let tmp = self.tmp;
-                    self.tmp += 1;
-                    self.output.push_str(&format!("\t%{tmp} = alloca [{len} x i8]\n"));
-                    self.output.push_str(&format!("\tstore [{len} x i8] c\"{value}\\00\", [{len} x i8]* %{tmp}\n"));
-                    format!("%{tmp}")
-                } else{
-                    format!("[{len} x i8] c\"{value}\\00\"")
-
-                }
- 
-
-            }
-            ASTNode::Identifier(id) => {
-          
-                self.get_varinfo(id).unwrap().0
-                
-                
-            },
-            ASTNode::FunctionCall{ fn_name, argument } => self.generate_code_funcall(fn_name, argument),
-
-            _ => "".to_string()
-        }
-
-    }
-
-    pub fn generate_code_fundef(&mut self,fn_name:String, parameters:Vec<(String, String)>, ret_type:Option<String>, body:Vec<ASTNode>){
-        let llvm_ret_type = match ret_type {
-            Some(ty) => {
-                if ty == "str".to_string(){
-                    "i8*".to_string()
-                }else{
-                    ty
-                }
-            }
-            None => "void".to_string(),
-        };
-        self.output.push_str(&format!(
-            "define {} @{}(",
-            llvm_ret_type, fn_name
-        ));
-
-        let mut tylist = Vec::new();
-        for p in parameters.clone(){
-            tylist.push(p.0);
-        }
-
-        let mut gen = String::new();
-
-        for (i, para) in parameters.iter().enumerate() {
-            
-            let llvm_para_type = turn_to_llvm_type(para.0.clone()).unwrap();
-            let para_name = &para.1;
-            if i > 0 {
-                self.output.push_str(", ");
-            }
-           
-            
-
-            let ltmp = self.tmp;
-            self.tmp += 1;
-            gen.push_str(format!("\t%{ltmp} = alloca {llvm_para_type}\n").as_str());
-            gen.push_str(format!("\tstore {llvm_para_type} %{para_name},ptr %{ltmp}\n").as_str());
-            self.output.push_str(&format!("{} %{}", llvm_para_type, para_name));
-
-           let varinfo = Info::Variable { tmp_name: format!("%{ltmp}"), ty:llvm_para_type.clone(), scope: 2, size: 32 };
-            self.add_to_symbol(self.scope, para_name.clone(), varinfo);
-        }
-
-        self.output.push_str(") {\n");
-        self.output.push_str("entry:\n");
-        self.output.push_str(&gen);
-        
-        self.tmp += 1;
-        self.scope += 1;
-       
-        if llvm_ret_type == "void".to_string(){
-            for stmt in body {
-                self.generate_statement(stmt);
-            }
-            self.output.push_str("ret void\n");
-
-        } else {
-            for stmt in body {
-                self.generate_statement(stmt);
-            }
-        }
-
-
-        self.output.push_str("}\n");
-        self.scope -= 1;
-        let funinfo = Info::Function { tmp_name: fn_name.clone(), ret_ty: llvm_ret_type.clone(), paras: tylist.clone(), scope: 1 };
-        self.add_to_symbol(self.scope, fn_name, funinfo);
-
-    }
-
-    fn generate_code_return(&mut self, ast:ASTNode){
-        let value = self.generate_code_expression(ast);
-     
-        
-        // let ty = var.ty.clone();
-        let tmp = self.tmp;
-        self.tmp += 1;
-        self.output.push_str(&format!("\t%{tmp} = load i32, i32* {value}\n"));
-        self.output.push_str(&format!("\tret i32 %{tmp}\n"));
-    }
-
-    fn generate_code_assignment(&mut self,  identifier: String, var_value: Option<Box<ASTNode>>,){  
-        let var = self.get_varinfo(identifier).unwrap();
-        let value = self.generate_code_expression(*var_value.unwrap());
-        let tmp =self.new_tmp();
-       
-        if var.2 != 1{
-            let ty = var.1;
-            let var_name = var.0;
-            self.output.push_str(format!("\t%{tmp} = load {ty}, ptr {value}\n").as_str());
-            self.output.push_str(format!("\tstore {ty} %{tmp}, ptr {var_name}\n").as_str());
-        }else{
-            let ty = var.1;
-            let var_name = var.0;
-            self.output.push_str(format!("\t%{tmp} = load {ty}, ptr {value}\n").as_str());
-            self.output.push_str(format!("\tstore {ty} %{tmp}, ptr {var_name}\n").as_str());
-        }
-        
-    }
-
-    fn generate_code_funcall(&mut self, fn_name:String, argument: Vec<ASTNode>)->String{
-      
-
-        let fun =self.get_funinfo(fn_name.clone()).unwrap();
-        let tylist = &fun.2;
-        let ret_type = fun.1.clone();
-        let mut values = Vec::new();
-
-        for i in 0..argument.len(){
-            let ast = argument.get(i).unwrap().clone();
-            let v = self.generate_code_expression(ast);
-            let t = tylist.get(i).unwrap();
-
-            // if tylist[i] != "i8*"{
-                let ptmp = self.tmp;
-                self.tmp += 1;
-                self.output.push_str(&format!("\t%{ptmp} = load {t} , {t}* {v}\n"));
-                values.push(format!("%{ptmp}"));
-            // } else{
-            //     values.push(format!("{v}"));
-            // }      
-        }
-
-        if ret_type == "void".to_string(){
-            self.output.push_str(&format!("\tcall void @{fn_name}("));
-            for i in 0..values.len(){
-                let v = values.get(i).unwrap();
-                let t = tylist.get(i).unwrap();
-                if v == values.last().unwrap() {
-                    self.output.push_str(&format!("{t} {v}"));
-                }else{
-                    self.output.push_str(&format!("{t} {v},"));
-                }
-                
-            }
-            let tmp2 = self.tmp;
-            self.tmp += 1;
-       
-
-            self.output.push_str(&format!(")\n"));
-            "".to_string()
-
-        }else{
-            let tmp = self.tmp;
-            self.tmp += 1;
-            self.output.push_str(&format!("\t%{tmp} = call {ret_type} @{fn_name}("));
-            for i in 0..values.len(){
-                let v = values.get(i).unwrap();
-                let t = tylist.get(i).unwrap();
-                if v == values.last().unwrap() {
-                    self.output.push_str(&format!("{t} {v}"));
-                }else{
-                    self.output.push_str(&format!("{t} {v},"));
-                }
-                
-            }
-            let tmp2 = self.tmp;
-        self.tmp += 1;
-       
-        self.output.push_str(&format!(")\n"));
-        self.output.push_str(&format!("\t%{tmp2} = alloca {ret_type} \n"));
-        self.output.push_str(&format!("\tstore {ret_type} %{tmp},ptr %{tmp2} \n"));
-        format!("%{tmp2}")
-        }
-
-    } 
-
-    fn generate_code_ifelse(&mut self,condition:Box<ASTNode>, if_body:Vec<ASTNode>, elif_body:Vec<Vec<ASTNode>>, el_condition: Vec<ASTNode>,else_body:Option<Vec<ASTNode>>) {
-        let res = self.generate_code_expression(*condition);
-        let mut jmp = 0;
-        
-        let tmp = self.new_tmp();
-        let tmp1 = self.new_tmp(); // if
-        let mut tmp2 = 0;// else
-        let mut tmp4 = 0; //elif
-        let tmp3 = self.new_tmp(); // final
-        if elif_body.len() > 0{
-            tmp2 = self.new_tmp(); 
-            tmp4 = self.new_tmp();
-            jmp = tmp4;
-        } else if else_body.is_some(){
-            tmp2 = self.new_tmp();
-            jmp = tmp2;
-        } else {
-            jmp = tmp3;
-        }
-        
-        self.output.push_str(format!("\t%{tmp} = load i1, ptr {res}\n").as_str());
-        self.output.push_str(format!("\tbr i1 %{tmp}, label %__{tmp1}, label %__{jmp}\n").as_str());
-        self.output.push_str(format!("__{tmp1}:\n").as_str());
-        for stat in if_body{
-            self.generate_statement(stat);
-        }
-        self.output.push_str(format!("\tbr label %__{tmp3}\n").as_str());
-
-      
-
-        
-        for (i, elif_cond) in el_condition.iter().enumerate() {
-            
-            self.output.push_str(format!("__{tmp4}:\n").as_str()); 
-            let cond = self.generate_code_expression(elif_cond.clone());
-            let tmp_el0 = self.new_tmp(); //elif bool
-            let tmp_el1 = self.new_tmp(); // elif body
-            let mut tmp_el2 = self.new_tmp(); // elif jmp
-            if i < el_condition.len()-1{
-                tmp4 = self.new_tmp();
-                tmp_el2 = tmp4;
-                
-            }else{
-                tmp_el2 = tmp2;
-            }
-            self.output.push_str(format!("\t%{tmp_el0} = load i1, ptr {cond}\n").as_str()); 
-            self.output.push_str(format!("\tbr i1 %{tmp_el0}, label %__{tmp_el1}, label %__{tmp_el2}\n").as_str());
-
-            self.output.push_str(format!("__{tmp_el1}:\n").as_str());
-            for stat in elif_body[i].clone() {
-                self.generate_statement(stat);
-            }
-            self.output.push_str(format!("\tbr label %__{tmp3}\n").as_str());
-
-            
-        }
-
-        match else_body{
-            Some(v) =>{
-                self.output.push_str(format!("__{tmp2}:\n").as_str());
-                for stat in v{
-                    self.generate_statement(stat);
-                }
-                self.output.push_str(format!("\tbr label %__{tmp3}\n").as_str());
-            }
-            None => (),
-        }
-       
-        self.output.push_str(format!("__{tmp3}:\n").as_str());
-
-
-
-
-    }
-
-    fn generate_code_while(&mut self,  condition:Box<ASTNode>, body:Vec<ASTNode>){
-        let tmp1 = self.new_tmp(); // bool
-        let tmp2 = self.new_tmp(); // true
-        let tmp3 = self.new_tmp(); // false
-        self.output.push_str(format!("\tbr label %__{tmp1}\n").as_str());
-        self.output.push_str(format!("__{tmp1}:\n").as_str());
-        let res = self.generate_code_expression(*condition);
-        let tmp = self.new_tmp();
-        self.output.push_str(format!("\t%{tmp} = load i1, ptr {res}\n").as_str());
-        self.output.push_str(format!("\tbr i1 %{tmp}, label %__{tmp2}, label %__{tmp3}\n").as_str());
-        self.output.push_str(format!("__{tmp2}:\n").as_str());
-        for stat in body{
-            self.generate_statement(stat);
-        }
-        self.output.push_str(format!("\tbr label %__{tmp1}\n").as_str());
-
-        self.output.push_str(format!("__{tmp3}:\n").as_str());
-
-
-
-    }
-
-}
-
-
-fn turn_to_llvm_type(ty: String) -> Result<String, String> {
-    match ty.as_str() {
-        "i32" => Ok("i32".to_string()),
-        "str" => Ok("i8*".to_string()),
-        "ptr" => Ok("ptr".to_string()),
-        "void" => Ok("void".to_string()),
-        "i1" => Ok("i1".to_string()),
-        _ => Err(format!("Cannot turn type '{}' to LLVM type", ty)),
-    }
-}
-
-fn turn_string_to_int(str: String) -> i32{
-    str.parse::<i32>().unwrap()
-}
-
-
+use crate::parser::Binding;
+use crate::tast::{Expr, ExprKind, Function, Item, Program, Stmt, Var};
+use crate::types::{Type, TypeKind, Types};
+use crate::SrcInfo;
+use std::collections::HashMap;
+use crate::codegen_lib::{generate_lib, lib_symbol, user_symbol};
+use crate::sema::{is_comparison, lib_functions};
+pub struct Codegen {
+    output: String,
+    globals: String,
+    tmp: i64,
+    scope: usize,
+    types: Types,
+    /// The return type of the function being generated
+    ret_type: Type,
+    pub sym_table: Vec<HashMap<String, Info>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Info{
+    Variable{
+        tmp_name:String,
+        ty:Type,
+        scope: usize,
+        size: i64,
+    },
+    Function{
+        tmp_name:String,
+        /// A `TypeKind::Function`
+        ty: Type,
+        scope:usize,
+    }
+}
+
+type FunInfo = (String, Type, Vec<Type>, usize);
+type VarInfo = (String, Type, usize, i64);
+
+impl Default for Codegen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codegen {
+    pub fn new() -> Self {
+        Codegen {
+            output: String::new(),
+            globals: String::new(),
+            tmp: 0,
+            scope: 1,
+            types: Types::new(),
+            ret_type: Type::VOID,
+            sym_table: Vec::new(),
+        }
+    }
+
+    fn new_tmp(&mut self) -> i64{
+        let tmp = self.tmp;
+        self.tmp += 1;
+        tmp
+    }
+
+    fn add_to_symbol(&mut self, scope:usize, name:String, info:Info){
+
+        let t = &mut self.sym_table;
+        let a = t.get_mut(scope).unwrap();
+        a.insert(name, info);
+    }
+
+    fn push_scope(&mut self){
+        self.scope += 1;
+        self.sym_table.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self){
+        self.scope -= 1;
+        self.sym_table.pop();
+    }
+
+    fn get_funinfo(&self, name: String) -> Option<FunInfo>{
+        let fun =self.sym_table.get(1).unwrap().get(&name).unwrap().clone();
+        match fun {
+            Info::Function { tmp_name, ty, scope } => match self.types.kind(ty) {
+                TypeKind::Function { params, ret } => Some((tmp_name, *ret, params.clone(), scope)),
+                _ => None,
+            },
+            _ => None
+        }
+    }
+
+    fn llvm_type(&self, ty: Type) -> String{
+        self.types.llvm_type(ty)
+    }
+
+    fn get_varinfo(&self, name:String)->Option<VarInfo>{
+        let mut s =self.scope;
+        while s >= 1{
+            let a = self.sym_table.get(s ).unwrap();
+            let v = a.get(&name).cloned();
+            if v.is_none(){
+                s -= 1;
+            }else{
+                break;
+            }
+        }
+
+        if s < 1{
+            None
+        }else{
+            let var =self.sym_table.get(s).unwrap().get(&name).cloned().unwrap().clone();
+            match var {
+                Info::Variable { tmp_name, ty, scope, size }
+                   => Some((tmp_name, ty, scope, size)),
+                _ => None
+            }
+        }
+    }
+
+    pub fn generate_code(&mut self, mut program:Program, info:SrcInfo) -> &String{
+        self.types = std::mem::take(&mut program.types);
+
+        // table 1 holds the globals and functions, each function and block pushes its own
+        self.sym_table.push(HashMap::new());
+        self.sym_table.push(HashMap::new());
+
+        self.generate_program(program, info);
+        let globals = std::mem::take(&mut self.globals);
+        self.output.push_str(&globals);
+        &self.output
+    }
+
+    pub fn generate_program(&mut self, program:Program, info:SrcInfo) {
+
+        let tt =info.target_triple;
+        self.output.push_str(&format!("target triple = \"{tt}\"\n"));
+
+        let v = &generate_lib(&self.types);
+        for i in v{
+            self.output.push_str(i);
+        }
+
+        for func in lib_functions(){
+            let ty = self.types.function(func.paras.iter().map(|p| p.0).collect(), func.ret_type);
+            self.add_to_symbol(1, func.fn_name.clone(), Info::Function { tmp_name: lib_symbol(&func.fn_name), ty, scope: 1 });
+        }
+        self.declare_items(&program.items);
+        for item in program.items{
+            match item {
+                Item::Function(func) => self.generate_code_fundef(func),
+                Item::Global(var) => self.generate_code_vardef(var),
+            }
+        }
+    }
+
+    /// Registers every function and global up front, so code can refer to
+    /// ones defined further down the file.
+    fn declare_items(&mut self, items:&[Item]){
+        for item in items{
+            match item {
+                Item::Function(func) => {
+                    // `main` is wrapped by a C-compatible entry point, see generate_code_main_wrapper
+                    let symbol = user_symbol(&func.name);
+                    let ty = self.types.function(func.params.iter().map(|p| p.0).collect(), func.ret_type);
+                    self.add_to_symbol(1, func.name.clone(), Info::Function { tmp_name: symbol, ty, scope: 1 });
+                }
+                Item::Global(var) => {
+                    let tmp = self.new_tmp();
+                    let varinfo = Info::Variable { tmp_name: format!("@g{tmp}"), ty: var.ty, scope: 1, size: 32 };
+                    self.add_to_symbol(1, var.name.clone(), varinfo);
+                }
+            }
+        }
+    }
+
+    pub fn generate_statement(&mut self, stat:Stmt){
+        match stat{
+            Stmt::Var(var) => self.generate_code_vardef(var),
+            Stmt::Assign { name, value } => self.generate_code_assignment(name, value),
+            Stmt::Return(expr) => self.generate_code_return(expr),
+            Stmt::If { cond, then_body, elifs, else_body } => self.generate_code_ifelse(cond, then_body, elifs, else_body),
+            Stmt::While { cond, body } => self.generate_code_while(cond, body),
+            Stmt::Expr(expr) => {self.generate_code_expression(expr);},
+        }
+    }
+
+    pub fn generate_code_vardef(&mut self, var:Var){
+        let Var { ty, name: identifier, value: var_value, binding } = var;
+        let llvm_var_type = self.llvm_type(ty);
+
+        if self.scope != 1{
+            let tmp = self.new_tmp();
+            self.output.push_str(&format!("\t%t{tmp} = alloca {llvm_var_type}\n"));
+
+            if let Some(expr) = var_value {
+                let value = self.generate_code_expression(expr);
+                let tmp2 = self.new_tmp();
+                self.output.push_str(&format!("\t%t{tmp2} = load {llvm_var_type}, ptr {value}\n"));
+                self.output.push_str(&format!("\tstore {llvm_var_type} %t{tmp2}, ptr %t{tmp}\n"));
+            }
+            let varinfo = Info::Variable { tmp_name: format!("%t{tmp}"), ty, scope: self.scope, size: 32 };
+            self.add_to_symbol(self.scope, identifier, varinfo);
+        } else{
+            let global = self.get_varinfo(identifier).unwrap().0;
+            let value = match var_value{
+                Some(expr) => self.generate_code_expression(expr),
+                None => "zeroinitializer".to_string(),
+            };
+            // only `mut` globals are ever written
+            let kind = if binding == Binding::Mutable { "global" } else { "constant" };
+            self.output.push_str(&format!("{global} = {kind} {llvm_var_type} {value}\n"));
+        }
+
+    }
+
+    fn generate_code_expression(&mut self, expr:Expr) -> String{
+        match expr.kind{
+            ExprKind::Binary {
+                left,
+                op,
+                right
+            } => {
+
+                let operand_ty = left.ty;
+                let ty = self.llvm_type(operand_ty);
+
+                let left = self.generate_code_expression(*left);
+
+
+                let right = self.generate_code_expression(*right);
+
+                if operand_ty == Type::STR{
+                    return self.generate_code_string_infix(left, op, right);
+                }
+
+                let tmp_left = self.new_tmp();
+                self.output.push_str(format!("\t%t{tmp_left} = load {ty}, ptr {left}\n").as_str());
+
+                let tmp_right = self.new_tmp();
+                self.output.push_str(format!("\t%t{tmp_right} = load {ty}, ptr {right}\n").as_str());
+
+                let tmp_res = self.new_tmp();
+                let instruction = binary_instruction(&op, operand_ty);
+                let res_ty = self.llvm_type(expr.ty);
+                self.output.push_str(format!("\t%t{tmp_res} = {instruction} {ty} %t{tmp_left}, %t{tmp_right}\n").as_str());
+
+                let tmp_new = self.new_tmp();
+                self.output.push_str(format!("\t%t{tmp_new} = alloca {res_ty}\n").as_str());
+                self.output.push_str(format!("\tstore {res_ty} %t{tmp_res}, ptr %t{tmp_new}\n").as_str());
+                format!("%t{tmp_new}")
+            },
+            ExprKind::Int(num) => self.generate_code_constant(Type::I32, num.to_string()),
+            ExprKind::Float(num) => {
+                let bits = num.to_bits();
+                self.generate_code_constant(Type::F64, format!("0x{bits:016X}"))
+            },
+            ExprKind::Bool(b) => self.generate_code_constant(Type::BOOL, b.to_string()),
+            ExprKind::Char(c) => self.generate_code_constant(Type::CHAR, (c as u8).to_string()),
+            ExprKind::Str(value)=>{
+                let constant = self.generate_string_constant(&value);
+                if self.scope != 1{
+                    let tmp = self.new_tmp();
+                    self.output.push_str(&format!("\t%t{tmp} = alloca %str\n"));
+                    self.output.push_str(&format!("\tstore %str {constant}, ptr %t{tmp}\n"));
+                    format!("%t{tmp}")
+                } else{
+                    constant
+                }
+
+
+            }
+            ExprKind::Slice { string, start, end } => self.generate_code_slice(*string, start, end),
+            ExprKind::Interpolation(parts) => self.generate_code_interpolation(parts),
+            ExprKind::Index { array, index } => self.generate_code_index(*array, *index, expr.ty),
+            ExprKind::Len(receiver) => {
+                let ty = self.llvm_type(receiver.ty);
+                let s = self.generate_code_expression(*receiver);
+                let len = self.load_len(&s, &ty);
+                let tmp = self.new_tmp();
+                self.output.push_str(&format!("\t%t{tmp} = alloca i32\n"));
+                self.output.push_str(&format!("\tstore i32 {len}, ptr %t{tmp}\n"));
+                format!("%t{tmp}")
+            }
+            ExprKind::Zero => self.generate_code_constant(expr.ty, "zeroinitializer".to_string()),
+            ExprKind::Variable(id) => self.get_varinfo(id).unwrap().0,
+            ExprKind::Global(id) => match &self.sym_table[1][&id] {
+                Info::Variable { tmp_name, .. } => tmp_name.clone(),
+                _ => unreachable!("sema only lets @ name globals"),
+            },
+            ExprKind::Call{ name, args } => self.generate_code_funcall(name, args),
+        }
+
+    }
+
+    /// Literals live in a stack slot inside functions and are used as plain
+    /// constants in global initialisers.
+    fn generate_code_constant(&mut self, ty:Type, value:String) -> String{
+        if self.scope != 1{
+            let ty = self.llvm_type(ty);
+            let tmp = self.new_tmp();
+            self.output.push_str(&format!("\t%t{tmp} = alloca {ty}\n"));
+            self.output.push_str(&format!("\tstore {ty} {value}, ptr %t{tmp}\n"));
+            format!("%t{tmp}")
+        } else{
+            value
+        }
+    }
+
+    /// Emits the bytes of a string literal as a private global and returns
+    /// the `%str` constant pointing at them.
+    fn generate_string_constant(&mut self, value:&str) -> String{
+        let tmp = self.new_tmp();
+        let len = value.len();
+        let bytes = escape_llvm_string(value);
+        self.globals.push_str(&format!("@.str.{tmp} = private unnamed_addr constant [{} x i8] c\"{bytes}\\00\"\n", len + 1));
+        format!("{{ ptr @.str.{tmp}, i32 {len} }}")
+    }
+
+    /// Length of the `%str` or `%arr` value behind `s`.
+    fn load_len(&mut self, s:&str, ty:&str) -> String{
+        let tmp = self.new_tmp();
+        let len = self.new_tmp();
+        self.output.push_str(&format!("\t%t{tmp} = load {ty}, ptr {s}\n"));
+        self.output.push_str(&format!("\t%t{len} = extractvalue {ty} %t{tmp}, 1\n"));
+        format!("%t{len}")
+    }
+
+    /// The element slot itself is the value of an index expression, so no
+    /// copy is made.
+    fn generate_code_index(&mut self, expr:Expr, index:Expr, elem_type:Type) -> String{
+        let elem_ty = self.llvm_type(elem_type);
+        let arr = self.generate_code_expression(expr);
+        let idx = self.generate_code_expression(index);
+
+        let tmp_arr = self.new_tmp();
+        let tmp_ptr = self.new_tmp();
+        let tmp_len = self.new_tmp();
+        let tmp_idx = self.new_tmp();
+        let tmp_wide = self.new_tmp();
+        let tmp_elem = self.new_tmp();
+        self.output.push_str(&format!("\t%t{tmp_arr} = load %arr, ptr {arr}\n"));
+        self.output.push_str(&format!("\t%t{tmp_ptr} = extractvalue %arr %t{tmp_arr}, 0\n"));
+        self.output.push_str(&format!("\t%t{tmp_len} = extractvalue %arr %t{tmp_arr}, 1\n"));
+        self.output.push_str(&format!("\t%t{tmp_idx} = load i32, ptr {idx}\n"));
+        self.output.push_str(&format!("\tcall void @ika_bounds_check(i32 %t{tmp_idx}, i32 %t{tmp_len})\n"));
+        self.output.push_str(&format!("\t%t{tmp_wide} = sext i32 %t{tmp_idx} to i64\n"));
+        self.output.push_str(&format!("\t%t{tmp_elem} = getelementptr {elem_ty}, ptr %t{tmp_ptr}, i64 %t{tmp_wide}\n"));
+        format!("%t{tmp_elem}")
+    }
+
+    fn generate_code_string_infix(&mut self, left:String, op:String, right:String) -> String{
+        let tmp_left = self.new_tmp();
+        self.output.push_str(&format!("\t%t{tmp_left} = load %str, ptr {left}\n"));
+        let tmp_right = self.new_tmp();
+        self.output.push_str(&format!("\t%t{tmp_right} = load %str, ptr {right}\n"));
+
+        let tmp_res = self.new_tmp();
+        let ty = if op == "+"{
+            self.output.push_str(&format!("\t%t{tmp_res} = call %str @ika_str_concat(%str %t{tmp_left}, %str %t{tmp_right})\n"));
+            "%str"
+        } else {
+            let cond = comparison_cond(&op);
+            let tmp_cmp = self.new_tmp();
+            self.output.push_str(&format!("\t%t{tmp_cmp} = call i32 @ika_str_cmp(%str %t{tmp_left}, %str %t{tmp_right})\n"));
+            self.output.push_str(&format!("\t%t{tmp_res} = icmp {cond} i32 %t{tmp_cmp}, 0\n"));
+            "i1"
+        };
+
+        let tmp_new = self.new_tmp();
+        self.output.push_str(&format!("\t%t{tmp_new} = alloca {ty}\n"));
+        self.output.push_str(&format!("\tstore {ty} %t{tmp_res}, ptr %t{tmp_new}\n"));
+        format!("%t{tmp_new}")
+    }
+
+    fn generate_code_interpolation(&mut self, parts:Vec<Expr>) -> String{
+        let mut result: Option<String> = None;
+        for part in parts{
+            if matches!(&part.kind, ExprKind::Str(s) if s.is_empty()){
+                continue;
+            }
+            let ty = part.ty;
+            let value = self.generate_code_expression(part);
+            let value = self.generate_code_format(value, ty);
+            result = Some(match result{
+                Some(acc) => self.generate_code_string_infix(acc, "+".to_string(), value),
+                None => value,
+            });
+        }
+
+        match result{
+            Some(value) => value,
+            None => self.generate_code_expression(Expr::new(ExprKind::Str(String::new()), Type::STR)),
+        }
+    }
+
+    /// Turns the value behind `value` into a `%str` using the runtime
+    /// formatting helpers.
+    fn generate_code_format(&mut self, value:String, ty:Type) -> String{
+        let helper = match ty{
+            Type::I32 => lib_symbol("itos"),
+            Type::BOOL => lib_symbol("btos"),
+            Type::CHAR => lib_symbol("ctos"),
+            Type::F64 => lib_symbol("ftos"),
+            _ => return value,
+        };
+        let ty = self.llvm_type(ty);
+        let tmp = self.new_tmp();
+        let tmp_res = self.new_tmp();
+        let tmp_new = self.new_tmp();
+        self.output.push_str(&format!("\t%t{tmp} = load {ty}, ptr {value}\n"));
+        self.output.push_str(&format!("\t%t{tmp_res} = call %str @{helper}({ty} %t{tmp})\n"));
+        self.output.push_str(&format!("\t%t{tmp_new} = alloca %str\n"));
+        self.output.push_str(&format!("\tstore %str %t{tmp_res}, ptr %t{tmp_new}\n"));
+        format!("%t{tmp_new}")
+    }
+
+    fn generate_code_slice(&mut self, expr:Expr, start:Option<Box<Expr>>, end:Option<Box<Expr>>) -> String{
+        let s = self.generate_code_expression(expr);
+        let from = match start{
+            Some(e) => {
+                let v = self.generate_code_expression(*e);
+                let tmp = self.new_tmp();
+                self.output.push_str(&format!("\t%t{tmp} = load i32, ptr {v}\n"));
+                format!("%t{tmp}")
+            }
+            None => "0".to_string(),
+        };
+        let to = match end{
+            Some(e) => {
+                let v = self.generate_code_expression(*e);
+                let tmp = self.new_tmp();
+                self.output.push_str(&format!("\t%t{tmp} = load i32, ptr {v}\n"));
+                format!("%t{tmp}")
+            }
+            None => self.load_len(&s, "%str"),
+        };
+
+        let tmp_s = self.new_tmp();
+        let tmp_res = self.new_tmp();
+        let tmp_new = self.new_tmp();
+        self.output.push_str(&format!("\t%t{tmp_s} = load %str, ptr {s}\n"));
+        self.output.push_str(&format!("\t%t{tmp_res} = call %str @ika_str_slice(%str %t{tmp_s}, i32 {from}, i32 {to})\n"));
+        self.output.push_str(&format!("\t%t{tmp_new} = alloca %str\n"));
+        self.output.push_str(&format!("\tstore %str %t{tmp_res}, ptr %t{tmp_new}\n"));
+        format!("%t{tmp_new}")
+    }
+
+    pub fn generate_code_fundef(&mut self, func:Function){
+        let Function { name: fn_name, params: parameters, ret_type, body } = func;
+        let llvm_ret_type = self.llvm_type(ret_type);
+        self.ret_type = ret_type;
+        let symbol = self.get_funinfo(fn_name.clone()).unwrap().0;
+        self.output.push_str(&format!(
+            "define {} @{}(",
+            llvm_ret_type, symbol
+        ));
+
+        let mut tylist = Vec::new();
+        let mut gen = String::new();
+
+        self.push_scope();
+
+        for (i, para) in parameters.iter().enumerate() {
+
+            let llvm_para_type = self.llvm_type(para.0);
+            let para_name = &para.1;
+            if i > 0 {
+                self.output.push_str(", ");
+            }
+
+            let ltmp = self.new_tmp();
+            gen.push_str(format!("\t%t{ltmp} = alloca {llvm_para_type}\n").as_str());
+            gen.push_str(format!("\tstore {llvm_para_type} %arg.{para_name}, ptr %t{ltmp}\n").as_str());
+            self.output.push_str(&format!("{} %arg.{}", llvm_para_type, para_name));
+
+            tylist.push(para.0);
+            let varinfo = Info::Variable { tmp_name: format!("%t{ltmp}"), ty: para.0, scope: self.scope, size: 32 };
+            self.add_to_symbol(self.scope, para_name.clone(), varinfo);
+        }
+
+        self.output.push_str(") {\n");
+        self.output.push_str("entry:\n");
+        self.output.push_str(&gen);
+
+        for stmt in body {
+            self.generate_statement(stmt);
+        }
+        if ret_type == Type::VOID{
+            self.output.push_str("\tret void\n");
+        } else {
+            // sema checked that every path returns, so falling off the end cannot happen
+            self.output.push_str("\tunreachable\n");
+        }
+
+        self.output.push_str("}\n");
+        self.pop_scope();
+
+        if fn_name == "main"{
+            self.generate_code_main_wrapper(!tylist.is_empty(), ret_type);
+        }
+
+    }
+
+    /// Emits `main(i32, ptr)` which turns argv into a `str[]` for the user's `main`
+    /// and makes its result the process exit code.
+    fn generate_code_main_wrapper(&mut self, has_args:bool, ret_type:Type){
+        self.output.push_str("define i32 @main(i32 %argc, ptr %argv) {\n");
+        self.output.push_str("entry:\n");
+        let symbol = user_symbol("main");
+        let args = if has_args{
+            self.output.push_str("\t%args = call %arr @ika_args(i32 %argc, ptr %argv)\n");
+            "%arr %args"
+        } else {
+            ""
+        };
+        if ret_type == Type::VOID{
+            self.output.push_str(&format!("\tcall void @{symbol}({args})\n"));
+            self.output.push_str("\tret i32 0\n");
+        } else {
+            self.output.push_str(&format!("\t%code = call i32 @{symbol}({args})\n"));
+            self.output.push_str("\tret i32 %code\n");
+        }
+        self.output.push_str("}\n");
+    }
+
+    fn generate_code_return(&mut self, ast:Option<Expr>){
+        let Some(ast) = ast else {
+            self.output.push_str("\tret void\n");
+            return;
+        };
+        let value = self.generate_code_expression(ast);
+        let ty = self.llvm_type(self.ret_type);
+
+        let tmp = self.new_tmp();
+        self.output.push_str(&format!("\t%t{tmp} = load {ty}, ptr {value}\n"));
+        self.output.push_str(&format!("\tret {ty} %t{tmp}\n"));
+    }
+
+    fn generate_code_assignment(&mut self,  identifier: String, var_value: Expr){
+        let var = self.get_varinfo(identifier).unwrap();
+        let value = self.generate_code_expression(var_value);
+        let tmp =self.new_tmp();
+
+        let ty = self.llvm_type(var.1);
+        let var_name = var.0;
+        self.output.push_str(format!("\t%t{tmp} = load {ty}, ptr {value}\n").as_str());
+        self.output.push_str(format!("\tstore {ty} %t{tmp}, ptr {var_name}\n").as_str());
+
+    }
+
+    fn generate_code_funcall(&mut self, fn_name:String, argument: Vec<Expr>)->String{
+
+
+        let fun =self.get_funinfo(fn_name).unwrap();
+        let symbol = &fun.0;
+        let tylist = &fun.2;
+        let ret_type = fun.1;
+        let mut values = Vec::new();
+
+        for (ast, t) in argument.into_iter().zip(tylist.iter()){
+            let t = self.llvm_type(*t);
+            let v = self.generate_code_expression(ast);
+
+            let ptmp = self.new_tmp();
+            self.output.push_str(&format!("\t%t{ptmp} = load {t}, ptr {v}\n"));
+            values.push(format!("{t} %t{ptmp}"));
+        }
+        let args = values.join(", ");
+
+        if ret_type == Type::VOID{
+            self.output.push_str(&format!("\tcall void @{symbol}({args})\n"));
+            "".to_string()
+
+        }else{
+            let ret_type = self.llvm_type(ret_type);
+            let tmp = self.new_tmp();
+            self.output.push_str(&format!("\t%t{tmp} = call {ret_type} @{symbol}({args})\n"));
+            let tmp2 = self.new_tmp();
+            self.output.push_str(&format!("\t%t{tmp2} = alloca {ret_type}\n"));
+            self.output.push_str(&format!("\tstore {ret_type} %t{tmp}, ptr %t{tmp2}\n"));
+            format!("%t{tmp2}")
+        }
+
+    }
+
+    /// Locals declared in `body` go out of scope at its end, so a later
+    /// statement using the same name sees the outer variable again.
+    fn generate_block(&mut self, body:Vec<Stmt>){
+        self.push_scope();
+        for stat in body{
+            self.generate_statement(stat);
+        }
+        self.pop_scope();
+    }
+
+    fn generate_code_ifelse(&mut self,condition:Expr, if_body:Vec<Stmt>, elifs:Vec<(Expr, Vec<Stmt>)>, else_body:Option<Vec<Stmt>>) {
+        let (el_condition, elif_body): (Vec<_>, Vec<_>) = elifs.into_iter().unzip();
+        let res = self.generate_code_expression(condition);
+
+        let tmp = self.new_tmp();
+        let tmp1 = self.new_tmp(); // if
+        let mut tmp2 = 0;// else
+        let mut tmp4 = 0; //elif
+        let tmp3 = self.new_tmp(); // final
+        let jmp = if !elif_body.is_empty(){
+            tmp2 = self.new_tmp();
+            tmp4 = self.new_tmp();
+            tmp4
+        } else if else_body.is_some(){
+            tmp2 = self.new_tmp();
+            tmp2
+        } else {
+            tmp3
+        };
+        if else_body.is_none(){
+            tmp2 = tmp3;
+        }
+
+        self.output.push_str(format!("\t%t{tmp} = load i1, ptr {res}\n").as_str());
+        self.output.push_str(format!("\tbr i1 %t{tmp}, label %__{tmp1}, label %__{jmp}\n").as_str());
+        self.output.push_str(format!("__{tmp1}:\n").as_str());
+        self.generate_block(if_body);
+        self.output.push_str(format!("\tbr label %__{tmp3}\n").as_str());
+
+
+
+
+        for (i, elif_cond) in el_condition.iter().enumerate() {
+
+            self.output.push_str(format!("__{tmp4}:\n").as_str());
+            let cond = self.generate_code_expression(elif_cond.clone());
+            let tmp_el0 = self.new_tmp(); //elif bool
+            let tmp_el1 = self.new_tmp(); // elif body
+            let tmp_el2 = if i < el_condition.len()-1{
+                tmp4 = self.new_tmp();
+                tmp4
+            }else{
+                tmp2
+            }; // elif jmp
+            self.output.push_str(format!("\t%t{tmp_el0} = load i1, ptr {cond}\n").as_str());
+            self.output.push_str(format!("\tbr i1 %t{tmp_el0}, label %__{tmp_el1}, label %__{tmp_el2}\n").as_str());
+
+            self.output.push_str(format!("__{tmp_el1}:\n").as_str());
+            self.generate_block(elif_body[i].clone());
+            self.output.push_str(format!("\tbr label %__{tmp3}\n").as_str());
+
+
+        }
+
+        if let Some(v) = else_body {
+            self.output.push_str(format!("__{tmp2}:\n").as_str());
+            self.generate_block(v);
+            self.output.push_str(format!("\tbr label %__{tmp3}\n").as_str());
+        }
+
+        self.output.push_str(format!("__{tmp3}:\n").as_str());
+
+
+
+
+    }
+
+    fn generate_code_while(&mut self,  condition:Expr, body:Vec<Stmt>){
+        let tmp1 = self.new_tmp(); // bool
+        let tmp2 = self.new_tmp(); // true
+        let tmp3 = self.new_tmp(); // false
+        self.output.push_str(format!("\tbr label %__{tmp1}\n").as_str());
+        self.output.push_str(format!("__{tmp1}:\n").as_str());
+        let res = self.generate_code_expression(condition);
+        let tmp = self.new_tmp();
+        self.output.push_str(format!("\t%t{tmp} = load i1, ptr {res}\n").as_str());
+        self.output.push_str(format!("\tbr i1 %t{tmp}, label %__{tmp2}, label %__{tmp3}\n").as_str());
+        self.output.push_str(format!("__{tmp2}:\n").as_str());
+        self.generate_block(body);
+        self.output.push_str(format!("\tbr label %__{tmp1}\n").as_str());
+
+        self.output.push_str(format!("__{tmp3}:\n").as_str());
+
+
+
+    }
+
+}
+
+
+/// The instruction for `left op right` on operands of type `ty`.
+fn binary_instruction(op: &str, ty: Type) -> String{
+    let float = ty == Type::F64;
+    if is_comparison(op){
+        return if float {
+            format!("fcmp {}", float_comparison_cond(op))
+        } else {
+            format!("icmp {}", comparison_cond(op))
+        };
+    }
+
+    let instruction = match op {
+        "+" if float => "fadd",
+        "-" if float => "fsub",
+        "*" if float => "fmul",
+        "/" if float => "fdiv",
+        "%" if float => "frem",
+        "+" => "add",
+        "-" => "sub",
+        "*" => "mul",
+        "/" => "sdiv",
+        "%" => "srem",
+        "|" => "or",
+        "&" => "and",
+        _ => "xor",
+    };
+    instruction.to_string()
+}
+
+fn float_comparison_cond(op: &str) -> &'static str{
+    match op {
+        "==" => "oeq",
+        "!=" => "une",
+        "<" => "olt",
+        "<=" => "ole",
+        ">" => "ogt",
+        _ => "oge",
+    }
+}
+
+fn comparison_cond(op: &str) -> &'static str{
+    match op {
+        "==" => "eq",
+        "!=" => "ne",
+        "<" => "slt",
+        "<=" => "sle",
+        ">" => "sgt",
+        _ => "sge",
+    }
+}
+
+/// Escapes a string for use inside an LLVM `c"..."` constant.
+fn escape_llvm_string(value: &str) -> String{
+    let mut out = String::new();
+    for b in value.bytes(){
+        if b.is_ascii_graphic() && b != b'"' && b != b'\\' || b == b' '{
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("\\{:02X}", b));
+        }
+    }
+    out
+}
+
@@ -1,12 +1,37 @@
-pub fn generate_lib() -> Vec<String>{
-    Vec::from([
-        format!("declare i32 @echo(i8*, i32) nounwind\n"),
-        format!("declare i8* @itos(i32) nounwind\n"),
-        format!("declare i32 @len(i8*) nounwind\n"),
-       
-        format!("@int_to_string.result =  external global [12 x i8] \n")
-    ])
-  
-}
-
-
+use crate::sema::lib_functions;
+use crate::types::Types;
+
+pub fn generate_lib(types: &Types) -> Vec<String>{
+    let mut lib = Vec::from([
+        "%str = type { ptr, i32 }\n".to_string(),
+        "%arr = type { ptr, i32 }\n".to_string(),
+        "declare %arr @ika_args(i32, ptr) nounwind\n".to_string(),
+        "declare void @ika_bounds_check(i32, i32) nounwind\n".to_string(),
+        "declare %str @ika_btos(i1) nounwind\n".to_string(),
+        "declare %str @ika_ctos(i8) nounwind\n".to_string(),
+        "declare %str @ika_ftos(double) nounwind\n".to_string(),
+        "declare %str @ika_str_concat(%str, %str) nounwind\n".to_string(),
+        "declare i32 @ika_str_cmp(%str, %str) nounwind\n".to_string(),
+        "declare %str @ika_str_slice(%str, i32, i32) nounwind\n".to_string(),
+    ]);
+
+    for func in lib_functions(){
+        let ret_type = types.llvm_type(func.ret_type);
+        let paras: Vec<String> = func.paras.into_iter().map(|p| types.llvm_type(p.0)).collect();
+        lib.push(format!("declare {ret_type} @{}({}) nounwind\n", lib_symbol(&func.fn_name), paras.join(", ")));
+    }
+    lib
+
+}
+
+/// Built-in functions are linked under an `ika_` prefix so that names like
+/// `exit` do not clash with the C library.
+pub fn lib_symbol(name: &str) -> String{
+    format!("ika_{name}")
+}
+
+/// User subs get an `ika_u_` prefix, so no name a program picks can clash
+/// with the C library or with the runtime.
+pub fn user_symbol(name: &str) -> String{
+    format!("ika_u_{name}")
+}
@@ -0,0 +1,119 @@
+//! Evaluates the expressions whose value is needed before the program runs:
+//! global initialisers and the values of `const` declarations.
+
+use crate::tast::{Expr, ExprKind};
+
+/// Folds a typed expression to a literal. `lookup` gives the value of a
+/// variable the expression reads, or says why that variable is not a constant.
+pub fn eval(expr: &Expr, lookup: &mut dyn FnMut(&Expr) -> Result<Expr, String>) -> Result<Expr, String>{
+    let kind = match &expr.kind {
+        ExprKind::Int(_) | ExprKind::Float(_) | ExprKind::Bool(_) | ExprKind::Char(_) | ExprKind::Str(_) =>
+            return Ok(expr.clone()),
+        ExprKind::Variable(_) | ExprKind::Global(_) => return lookup(expr),
+        ExprKind::Binary { left, op, right } => {
+            let left = eval(left, lookup)?;
+            let right = eval(right, lookup)?;
+            binary(&left.kind, op, &right.kind)?
+        }
+        ExprKind::Len(string) => match eval(string, lookup)?.kind {
+            ExprKind::Str(s) => ExprKind::Int(s.len() as i32),
+            _ => return Err("the length of an array is not constant".to_string()),
+        },
+        ExprKind::Call { name, .. } => return Err(format!("calling {name} is not constant")),
+        ExprKind::Index { .. } => return Err("indexing is not constant".to_string()),
+        ExprKind::Slice { .. } => return Err("slicing is not constant".to_string()),
+        ExprKind::Interpolation(_) => return Err("string interpolation is not constant".to_string()),
+        ExprKind::Zero => return Err("an unassigned value is not constant".to_string()),
+    };
+    Ok(Expr::new(kind, expr.ty))
+}
+
+/// `left op right` with the same results the generated code gives at run time.
+fn binary(left: &ExprKind, op: &str, right: &ExprKind) -> Result<ExprKind, String>{
+    let value = match (left, right) {
+        (ExprKind::Int(l), ExprKind::Int(r)) => {
+            let (l, r) = (*l, *r);
+            match op {
+                // `add`, `sub` and `mul` wrap
+                "+" => ExprKind::Int(l.wrapping_add(r)),
+                "-" => ExprKind::Int(l.wrapping_sub(r)),
+                "*" => ExprKind::Int(l.wrapping_mul(r)),
+                "/" | "%" if r == 0 => return Err(format!("`{l} {op} {r}` divides by zero")),
+                "/" => ExprKind::Int(l.checked_div(r).ok_or_else(|| format!("`{l} {op} {r}` overflows i32"))?),
+                "%" => ExprKind::Int(l.checked_rem(r).ok_or_else(|| format!("`{l} {op} {r}` overflows i32"))?),
+                _ => ExprKind::Bool(compare(l, op, r)),
+            }
+        }
+        (ExprKind::Float(l), ExprKind::Float(r)) => match op {
+            "+" => ExprKind::Float(l + r),
+            "-" => ExprKind::Float(l - r),
+            "*" => ExprKind::Float(l * r),
+            "/" => ExprKind::Float(l / r),
+            "%" => ExprKind::Float(l % r),
+            _ => ExprKind::Bool(compare(l, op, r)),
+        },
+        (ExprKind::Str(l), ExprKind::Str(r)) => match op {
+            "+" => ExprKind::Str(format!("{l}{r}")),
+            _ => ExprKind::Bool(compare(l, op, r)),
+        },
+        // chars are signed `i8`s
+        (ExprKind::Char(l), ExprKind::Char(r)) => ExprKind::Bool(compare(*l as u8 as i8, op, *r as u8 as i8)),
+        (ExprKind::Bool(l), ExprKind::Bool(r)) => ExprKind::Bool(compare(l, op, r)),
+        _ => unreachable!("sema only lets `{op}` combine operands of one type"),
+    };
+    Ok(value)
+}
+
+/// Float comparisons are false when either side is NaN, except `!=`, like `fcmp`.
+fn compare<T: PartialOrd>(l: T, op: &str, r: T) -> bool{
+    match op {
+        "==" => l == r,
+        "!=" => l != r,
+        "<" => l < r,
+        "<=" => l <= r,
+        ">" => l > r,
+        _ => l >= r,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type;
+
+    fn literal(kind: ExprKind, ty: Type) -> Expr{
+        Expr::new(kind, ty)
+    }
+
+    fn no_names(var: &Expr) -> Result<Expr, String>{
+        Err(format!("{var:?} is not a constant"))
+    }
+
+    #[test]
+    fn operators(){
+        let int = |n| literal(ExprKind::Int(n), Type::I32);
+        let string = |s: &str| literal(ExprKind::Str(s.to_string()), Type::STR);
+        let eval = |expr: Expr| eval(&expr, &mut no_names).map(|value| value.kind);
+
+        assert_eq!(eval(Expr::infix(Expr::infix(int(7), "%", int(4)), "*", int(2))), Ok(ExprKind::Int(6)));
+        assert_eq!(eval(Expr::infix(int(2147483647), "+", int(1))), Ok(ExprKind::Int(-2147483648)));
+        assert_eq!(eval(Expr::infix(string("ab"), "+", string("c"))), Ok(ExprKind::Str("abc".to_string())));
+        assert_eq!(eval(Expr::infix(string("ab"), "<", string("b"))), Ok(ExprKind::Bool(true)));
+        let nan = literal(ExprKind::Float(f64::NAN), Type::F64);
+        assert_eq!(eval(Expr::infix(nan.clone(), "!=", nan)), Ok(ExprKind::Bool(true)));
+        let len = literal(ExprKind::Len(Box::new(string("four"))), Type::I32);
+        assert_eq!(eval(len), Ok(ExprKind::Int(4)));
+        assert_eq!(eval(Expr::infix(int(1), "/", int(0))), Err("`1 / 0` divides by zero".to_string()));
+    }
+
+    #[test]
+    fn non_constants(){
+        let call = literal(ExprKind::Call { name: "read_int".to_string(), args: Vec::new() }, Type::I32);
+        assert_eq!(eval(&call, &mut no_names), Err("calling read_int is not constant".to_string()));
+
+        let var = literal(ExprKind::Variable("n".to_string()), Type::I32);
+        let mut lookup = |_: &Expr| Ok(literal(ExprKind::Int(5), Type::I32));
+        let sum = Expr::infix(var, "+", literal(ExprKind::Int(1), Type::I32));
+        assert_eq!(eval(&sum, &mut lookup).unwrap().kind, ExprKind::Int(6));
+    }
+}
@@ -1,6 +1,6 @@
-use std::fs;
-
-pub fn read_fs(path: &str) -> String{
-    fs::read_to_string(path).unwrap()
-}
-
+use std::fs;
+use std::io;
+
+pub fn read_fs(path: &str) -> io::Result<String>{
+    fs::read_to_string(path)
+}
@@ -1,11 +1,16 @@
-use std::iter::Peekable;
+use std::collections::VecDeque;
+use std::fmt;
 use std::str::Chars;
+use serde::Serialize;
 
-#[derive(Debug,PartialEq,Clone, Copy)]
+#[derive(Debug,PartialEq,Clone, Copy, Serialize)]
+#[allow(dead_code, clippy::upper_case_acronyms)]
 pub enum TokenType{
     KEYWORD,
     ID,
     NUMBER,
+    FLOAT,
+    BOOL,
     CHAR,
     STRING,
     EQUALS,
@@ -36,39 +41,91 @@ pub enum TokenType{
     COMMA,
     ARROW,
     AT,
+    LBRACKET,
+    RBRACKET,
+    HASH,
+    DOT,
+    RANGE,
+    STRSTART,
+    STRMID,
+    STREND,
     EOF,
 }
 
 #[derive(Debug,PartialEq)]
 pub enum Error{
-    LexerErr,
+    /// `text` could not be turned into a token, it starts at `line`:`column`
+    LexerErr{text: String, line: usize, column: usize},
     ParserErr,
 }
 
-#[derive(Debug,PartialEq,Clone)]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self {
+            Error::LexerErr{text, line, column} => write!(f, "invalid token `{text}` at {line}:{column}"),
+            Error::ParserErr => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+impl From<Error> for String {
+    fn from(err: Error) -> Self{
+        err.to_string()
+    }
+}
+
+#[derive(Debug,PartialEq,Clone, Serialize)]
 pub struct Token{
     pub token_type: TokenType,
     pub value: String,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 pub struct LEXER<'a>{
-    src: Peekable<Chars<'a>>
+    source: &'a str,
+    src: Chars<'a>,
+    pending: VecDeque<Token>,
+    failed: bool,
+    error: Option<Error>,
 }
 
 impl<'a> LEXER<'a>{
     pub fn new(src: &'a str) -> Self {
-        Self{src:  src.chars().peekable()}
+        Self{source: src, src: src.chars(), pending: VecDeque::new(), failed: false, error: None}
+    }
+
+    fn peek(&self) -> Option<char>{
+        self.src.clone().next()
+    }
+
+    /// The byte offset of the next character
+    fn offset(&self) -> usize{
+        self.source.len() - self.src.as_str().len()
+    }
+
+    /// Remembers the first token that failed, which started at byte `start`.
+    fn fail(&mut self, start: usize){
+        if self.error.is_none(){
+            let before = &self.source[..start];
+            let line = before.matches('\n').count() + 1;
+            let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+            let text = self.source[start..self.offset()].to_string();
+            self.error = Some(Error::LexerErr{text, line, column});
+        }
     }
 
     pub fn next_token(&mut self) -> Option<Token>{
-        while let Some(&c) = self.src.peek(){
+        if let Some(token) = self.pending.pop_front(){
+            return Some(token);
+        }
+        while let Some(c) = self.peek(){
             
             if c.is_whitespace() {self.src.next();}
             else if c == '/'{
                 self.src.next();
-                if let Some(&next_c) = self.src.peek() {
+                if let Some(next_c) = self.peek() {
                     if next_c == '/' {
-                        while let Some(comment_char) = self.src.next() {
+                        for comment_char in self.src.by_ref() {
                             if comment_char == '\n' {
                                 break;
                             }
@@ -85,10 +142,15 @@ impl<'a> LEXER<'a>{
             else if c.is_alphabetic() || c == '_'{
                 return Some(self.collect_identifier_keyword());
                 
-            }else if c.is_digit(10){
+            }else if c.is_ascii_digit(){
                 return Some(self.collect_number());
             }else {
-                return Some(self.collect_symbol());
+                let start = self.offset();
+                let token = self.collect_symbol();
+                if self.failed{
+                    self.fail(start);
+                }
+                return Some(token);
 
             }
         }
@@ -98,7 +160,7 @@ impl<'a> LEXER<'a>{
     fn collect_identifier_keyword(&mut self)->Token{
         let mut value = String::new();
 
-        while let Some(&c) = self.src.peek(){
+        while let Some(c) = self.peek(){
             if c.is_alphanumeric() || c == '_'{
                 value.push(c);
                 self.src.next();
@@ -107,40 +169,39 @@ impl<'a> LEXER<'a>{
             }
         }
 
-        if value == String::from("i32")
-            || value == String::from("bool")
-            || value == String::from("str")
-            || value == String::from("ret")
-            || value == String::from("sub")
-            || value == String::from("if")
-            || value == String::from("else")
-            || value == String::from("elif")
-            || value == String::from("while")
-            || value == String::from("for")
-            || value == String::from("in")
-            || value == String::from("call")
+        if value == "i32"
+            || value == "bool"
+            || value == "str"
+            || value == "char"
+            || value == "f64"
+            || value == "ret"
+            || value == "sub"
+            || value == "if"
+            || value == "else"
+            || value == "elif"
+            || value == "while"
+            || value == "for"
+            || value == "in"
+            || value == "call"
+            || value == "const"
+            || value == "mut"
             
             
         {
             Token{
                 token_type: TokenType::KEYWORD,
-                value: value,
+                value,
             }
-        } else if value == "true".to_string(){
+        } else if value == "true" || value == "false"{
             Token{
-                token_type: TokenType::NUMBER,
-                value: "1".to_string(),
-            }
-        } else if value == "false".to_string(){
-            Token{
-                token_type: TokenType::NUMBER,
-                value: "0".to_string(),
+                token_type: TokenType::BOOL,
+                value,
             }
         }
         else {
             Token{
                 token_type: TokenType::ID,
-                value: value,
+                value,
             }
         }
         
@@ -150,24 +211,54 @@ impl<'a> LEXER<'a>{
 
     fn collect_number(&mut self)->Token{
         let mut value = String::new();
-        while let Some(&c) = self.src.peek(){
-            if c.is_digit(10){
+        self.collect_digits(&mut value);
+
+        // `1.5` is a float, while `1..3` is a range between two numbers
+        let mut ahead = self.src.clone();
+        if ahead.next() == Some('.') && ahead.next().is_some_and(|c| c.is_ascii_digit()){
+            self.src.next();
+            value.push('.');
+            self.collect_digits(&mut value);
+            return Token{
+                token_type: TokenType::FLOAT,
+                value,
+            };
+        }
+
+        Token{
+            token_type: TokenType::NUMBER,
+            value,
+        }
+    }
+
+    fn collect_digits(&mut self, value: &mut String){
+        while let Some(c) = self.peek(){
+            if c.is_ascii_digit(){
                 value.push(c);
                 self.src.next();
             }else{
                 break;
             }
         }
-        
+    }
+
+    fn collect_char(&mut self)->Token{
+        let mut value = String::new();
+        for c in self.src.by_ref(){
+            if c == '\''{
+                break;
+            }
+            value.push(c);
+        }
+
         Token{
-            token_type: TokenType::NUMBER,
-            value: value,
+            token_type: TokenType::CHAR,
+            value,
         }
     }
 
     fn collect_minus(&mut self) -> Token{
-        let c = self.src.peek().unwrap();
-        if *c == '>'{
+        if self.peek() == Some('>'){
             self.src.next();
             Token{
                 token_type: TokenType::ARROW,
@@ -179,30 +270,83 @@ impl<'a> LEXER<'a>{
         
     }
     
+    /// A plain literal becomes a single STRING token. A literal with `{expr}`
+    /// holes becomes STRSTART, the tokens of each hole separated by STRMID,
+    /// and a final STREND; `{{` and `}}` stand for literal braces.
     fn collect_string(&mut self)->Token{
+        let mut parts = Vec::new();
+        let mut holes = Vec::new();
         let mut value = String::new();
-        while let Some(&c) = self.src.peek(){
-            if c != '\"'{
-                value.push(c);
-                self.src.next();
-            }else{
-                self.src.next();
-                break;
+        while let Some(c) = self.src.next(){
+            match c {
+                '\"' => break,
+                '{' if self.peek() == Some('{') => {
+                    self.src.next();
+                    value.push('{');
+                }
+                '}' if self.peek() == Some('}') => {
+                    self.src.next();
+                    value.push('}');
+                }
+                '{' => {
+                    parts.push(std::mem::take(&mut value));
+                    holes.push(self.collect_hole());
+                }
+                _ => value.push(c),
             }
         }
-        
-        
+        parts.push(value);
+
+        if holes.is_empty(){
+            return Token{
+                token_type: TokenType::STRING,
+                value: parts.remove(0),
+            };
+        }
+
+        let count = holes.len();
+        for (i, hole) in holes.into_iter().enumerate(){
+            self.pending.extend(hole);
+            let token_type = if i + 1 == count { TokenType::STREND } else { TokenType::STRMID };
+            self.pending.push_back(Token{ token_type, value: std::mem::take(&mut parts[i + 1]) });
+        }
         Token{
-            token_type: TokenType::STRING,
-            value: value,
+            token_type: TokenType::STRSTART,
+            value: parts.remove(0),
         }
+    }
 
+    fn collect_hole(&mut self) -> Vec<Token>{
+        let mut inner = String::new();
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut closed = false;
+        for c in self.src.by_ref(){
+            match c {
+                '\"' => in_string = !in_string,
+                '{' if !in_string => depth += 1,
+                '}' if !in_string && depth == 0 => {
+                    closed = true;
+                    break;
+                }
+                '}' if !in_string => depth -= 1,
+                _ => (),
+            }
+            inner.push(c);
+        }
 
+        let mut lexer = LEXER::new(&inner);
+        match tokenization(&mut lexer){
+            Ok(tokens) if closed => tokens,
+            _ => {
+                self.failed = true;
+                Vec::new()
+            }
+        }
     }
 
     fn collect_eq(&mut self)-> Token{
-        let c = self.src.peek().unwrap();
-        if *c == '='{
+        if self.peek() == Some('='){
             self.src.next();
             Token{
                 token_type: TokenType::DEQUALS,
@@ -214,8 +358,7 @@ impl<'a> LEXER<'a>{
     }
 
     fn collect_lt(&mut self)-> Token{
-        let c = self.src.peek().unwrap();
-        if *c == '='{
+        if self.peek() == Some('='){
             self.src.next();
             Token{
                 token_type: TokenType::LE,
@@ -227,8 +370,7 @@ impl<'a> LEXER<'a>{
     }
     
     fn collect_st(&mut self)-> Token{
-        let c = self.src.peek().unwrap();
-        if *c == '='{
+        if self.peek() == Some('='){
             self.src.next();
             Token{
                 token_type: TokenType::SE,
@@ -239,9 +381,20 @@ impl<'a> LEXER<'a>{
         }
     }
 
+    fn collect_dot(&mut self)-> Token{
+        if self.peek() == Some('.'){
+            self.src.next();
+            Token{
+                token_type: TokenType::RANGE,
+                value:String::from(".."),
+            }
+        }else{
+            Token{token_type: TokenType::DOT, value:String::from(".")}
+        }
+    }
+
     fn collect_ex(&mut self)-> Token{
-        let c = self.src.peek().unwrap();
-        if *c == '='{
+        if self.peek() == Some('='){
             self.src.next();
             Token{
                 token_type: TokenType::UNEQ,
@@ -265,19 +418,26 @@ impl<'a> LEXER<'a>{
             Some('}') => Token{token_type: TokenType::RBRACE, value:String::from("}")},
             Some('(') => Token{token_type: TokenType::LPAREN, value:String::from("(")},
             Some(')') => Token{token_type: TokenType::RPAREN, value:String::from(")")},
-            Some('\'') => Token{token_type: TokenType::QUOTES, value:String::from("'")},
+            Some('[') => Token{token_type: TokenType::LBRACKET, value:String::from("[")},
+            Some(']') => Token{token_type: TokenType::RBRACKET, value:String::from("]")},
+            Some('.') => self.collect_dot(),
+            Some('\'') => self.collect_char(),
             Some('"') => self.collect_string(),
             Some('+') => Token{token_type: TokenType::ADD, value:String::from("+")},
             Some('-') => self.collect_minus(),
             Some('*') => Token{token_type: TokenType::ASTERISK, value:String::from("*")},
             Some(',') => Token{token_type: TokenType::COMMA, value:String::from(",")},
             Some('@') => Token{token_type: TokenType::AT, value:String::from("@")},
+            Some('#') => Token{token_type: TokenType::HASH, value:String::from("#")},
             Some('%') => Token{token_type: TokenType::REM, value:String::from("%")},
             Some('&') => Token{token_type: TokenType::AND, value:String::from("&")},
             Some('|') => Token{token_type: TokenType::OR, value:String::from("|")},
             Some('^') => Token{token_type: TokenType::XOR, value:String::from("^")},
             Some('!') => self.collect_ex(),
-            _ => Token{token_type:TokenType::EOF, value: String::from("")}
+            _ => {
+                self.failed = true;
+                Token{token_type:TokenType::EOF, value: String::from("")}
+            }
 
         }
 
@@ -294,6 +454,9 @@ pub fn tokenization(lexer: &mut LEXER) -> Result<Vec<Token>, Error>{
         tokens.push(token);
     }
 
+    if let Some(err) = lexer.error.take() {
+        return Err(err);
+    }
     Ok(tokens)
 }
 
@@ -332,7 +495,7 @@ mod tests{
 
         assert_eq!(tokens, right_result);
 
-        tokenization( &mut lexer);
+        tokenization( &mut lexer).unwrap();
     }
 
    
@@ -364,7 +527,7 @@ mod tests{
 
         assert_eq!(tokens, right_result);
 
-        tokenization( &mut lexer);
+        tokenization( &mut lexer).unwrap();
         
     }
 
@@ -399,7 +562,7 @@ mod tests{
 
         assert_eq!(tokens, right_result);
 
-        tokenization( &mut lexer);
+        tokenization( &mut lexer).unwrap();
         
 
     }
@@ -427,6 +590,82 @@ mod tests{
 
         assert_eq!(tokens, right_result);
 
-        tokenization( &mut lexer);
+        tokenization( &mut lexer).unwrap();
+    }
+
+    #[test]
+    fn slice(){
+        let input = "s[1..3].len()";
+
+        let mut lexer = LEXER::new(input);
+        let tokens = tokenization(&mut lexer).unwrap();
+
+        let right_result = vec![
+            Token { token_type: TokenType::ID, value: String::from("s") },
+            Token { token_type: TokenType::LBRACKET, value: String::from("[") },
+            Token { token_type: TokenType::NUMBER, value: String::from("1") },
+            Token { token_type: TokenType::RANGE, value: String::from("..") },
+            Token { token_type: TokenType::NUMBER, value: String::from("3") },
+            Token { token_type: TokenType::RBRACKET, value: String::from("]") },
+            Token { token_type: TokenType::DOT, value: String::from(".") },
+            Token { token_type: TokenType::ID, value: String::from("len") },
+            Token { token_type: TokenType::LPAREN, value: String::from("(") },
+            Token { token_type: TokenType::RPAREN, value: String::from(")") },
+        ];
+
+        assert_eq!(tokens, right_result);
+    }
+
+    #[test]
+    fn interpolation(){
+        let input = "\"x = {x}, {{y}} = {y + 1}!\"";
+
+        let mut lexer = LEXER::new(input);
+        let tokens = tokenization(&mut lexer).unwrap();
+
+        let right_result = vec![
+            Token { token_type: TokenType::STRSTART, value: String::from("x = ") },
+            Token { token_type: TokenType::ID, value: String::from("x") },
+            Token { token_type: TokenType::STRMID, value: String::from(", {y} = ") },
+            Token { token_type: TokenType::ID, value: String::from("y") },
+            Token { token_type: TokenType::ADD, value: String::from("+") },
+            Token { token_type: TokenType::NUMBER, value: String::from("1") },
+            Token { token_type: TokenType::STREND, value: String::from("!") },
+        ];
+
+        assert_eq!(tokens, right_result);
+    }
+
+    #[test]
+    fn unclosed_interpolation(){
+        let mut lexer = LEXER::new("\"x = {x\"");
+        let err = tokenization(&mut lexer).unwrap_err();
+        assert_eq!(err.to_string(), "invalid token `\"x = {x\"` at 1:1");
+    }
+
+    #[test]
+    fn unknown_character(){
+        let mut lexer = LEXER::new("i32 a = 1;\ni32 b = $;");
+        assert_eq!(tokenization(&mut lexer), Err(Error::LexerErr{text: "$".to_string(), line: 2, column: 9}));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn primitives(){
+        let input = "1.5 1..2 'c' true false";
+
+        let mut lexer = LEXER::new(input);
+        let tokens = tokenization(&mut lexer).unwrap();
+
+        let right_result = vec![
+            Token { token_type: TokenType::FLOAT, value: String::from("1.5") },
+            Token { token_type: TokenType::NUMBER, value: String::from("1") },
+            Token { token_type: TokenType::RANGE, value: String::from("..") },
+            Token { token_type: TokenType::NUMBER, value: String::from("2") },
+            Token { token_type: TokenType::CHAR, value: String::from("c") },
+            Token { token_type: TokenType::BOOL, value: String::from("true") },
+            Token { token_type: TokenType::BOOL, value: String::from("false") },
+        ];
+
+        assert_eq!(tokens, right_result);
+    }
+}
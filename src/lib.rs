@@ -0,0 +1,394 @@
+//! The ika compiler as a library. `compile_str` runs the whole pipeline,
+//! `lex`, `parse`, `analyze` and `codegen` run one stage each.
+
+pub mod lexer;
+pub mod lint;
+pub mod parser;
+pub mod tast;
+pub mod types;
+pub mod target;
+pub mod toolchain;
+mod sema;
+mod codegen;
+mod codegen_lib;
+mod consteval;
+mod runtime;
+mod opt;
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use clap::ValueEnum;
+use codegen::Codegen;
+use lexer::{LEXER, tokenization, Token, TokenType};
+use lint::LintLevels;
+use parser::{ASTNode, Parser};
+use sema::Context;
+use tast::Program;
+use toolchain::Toolchain;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Emit {
+    /// The token stream as JSON
+    Tokens,
+    /// The syntax tree as JSON
+    Ast,
+    /// The generated module, before the runtime is linked in
+    LlvmIr,
+    /// Assembly for the linked program
+    Asm,
+    /// An object file for the linked program
+    Obj,
+    /// An executable
+    Exe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Hash, ValueEnum)]
+pub enum OptLevel {
+    #[value(name = "0")]
+    O0,
+    #[value(name = "1")]
+    O1,
+    #[value(name = "2")]
+    O2,
+    #[value(name = "3")]
+    O3,
+    #[value(name = "s")]
+    Os,
+}
+
+impl OptLevel {
+    pub fn clang_flag(self) -> &'static str{
+        match self {
+            OptLevel::O0 => "-O0",
+            OptLevel::O1 => "-O1",
+            OptLevel::O2 => "-O2",
+            OptLevel::O3 => "-O3",
+            OptLevel::Os => "-Os",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub target_triple: String,
+    pub opt_level: OptLevel,
+    /// The last stage to run
+    pub emit: Emit,
+    /// Where the runtime and intermediate files are written
+    pub build_dir: PathBuf,
+    /// Base name for intermediate files
+    pub name: String,
+    /// Where `Asm`, `Obj` and `Exe` are written, assembly defaults to the build dir
+    pub output: Option<PathBuf>,
+    pub lints: LintLevels,
+    /// Zero locals that may be read before they are assigned, with a warning
+    /// instead of an error
+    pub zero_init: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            target_triple: target::host_triple(),
+            opt_level: OptLevel::O0,
+            emit: Emit::Exe,
+            build_dir: env::temp_dir().join("ika"),
+            name: "output".to_string(),
+            output: None,
+            lints: LintLevels::default(),
+            zero_init: false,
+        }
+    }
+}
+
+/// What the stages up to `Options::emit` produced.
+#[derive(Debug, Clone, Default)]
+pub struct Artifacts {
+    pub tokens: Vec<Token>,
+    pub ast: Option<ASTNode>,
+    pub llvm_ir: Option<String>,
+    pub asm: Option<String>,
+    /// The object file or executable
+    pub output: Option<PathBuf>,
+    /// Warnings, a compilation with errors returns those instead
+    pub warnings: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, message: message.into() }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, message: message.into() }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "error: {}", self.message),
+            Severity::Warning => write!(f, "warning: {}", self.message),
+        }
+    }
+}
+
+pub struct SrcInfo{
+   pub target_triple:String,
+}
+
+pub fn lex(source: &str) -> Result<Vec<Token>, Vec<Diagnostic>>{
+    let mut lexer = LEXER::new(source);
+    tokenization(&mut lexer).map_err(|err| vec![Diagnostic::error(err.to_string())])
+}
+
+pub fn parse(mut tokens: Vec<Token>) -> Result<ASTNode, Vec<Diagnostic>>{
+    tokens.push(Token {
+        token_type: TokenType::EOF,
+        value: String::new(),
+    });
+
+    let mut parser = Parser::new(tokens);
+    parser.parse_program().map_err(|err| vec![Diagnostic::error(err)])
+}
+
+/// Resolves names and types, an `Ok` here is a well-typed program and the
+/// warnings found on the way. Warnings also come before the error on failure.
+pub fn analyze(ast: ASTNode, options: &Options) -> Result<(Program, Vec<Diagnostic>), Vec<Diagnostic>>{
+    let mut ctx = Context { lints: options.lints.clone(), zero_init: options.zero_init, ..Context::new() };
+    let result = sema::check_program(ast, &mut ctx);
+    let mut diagnostics: Vec<Diagnostic> = ctx.warnings.into_iter().map(Diagnostic::warning).collect();
+    match result {
+        Ok(program) => Ok((program, diagnostics)),
+        Err(err) => {
+            diagnostics.push(Diagnostic::error(err));
+            Err(diagnostics)
+        }
+    }
+}
+
+/// Runs the front end only, no LLVM tools are needed.
+pub fn check(source: &str, options: &Options) -> Result<(Program, Vec<Diagnostic>), Vec<Diagnostic>>{
+    analyze(parse(lex(source)?)?, options)
+}
+
+pub fn codegen(program: Program, options: &Options) -> String{
+    let program = if options.opt_level == OptLevel::O0 { program } else { opt::fold_constants(program) };
+
+    let src_info = SrcInfo{
+        target_triple: options.target_triple.clone(),
+    };
+    let mut codegen = Codegen::new();
+    codegen.generate_code(program, src_info).clone()
+}
+
+pub fn compile_str(source: &str, options: &Options) -> Result<Artifacts, Vec<Diagnostic>>{
+    target::check_target(&options.target_triple).map_err(|err| vec![Diagnostic::error(err)])?;
+
+    let mut artifacts = Artifacts { tokens: lex(source)?, ..Artifacts::default() };
+    if options.emit == Emit::Tokens{
+        return Ok(artifacts);
+    }
+
+    let ast = parse(artifacts.tokens.clone())?;
+    artifacts.ast = Some(ast.clone());
+    if options.emit == Emit::Ast{
+        return Ok(artifacts);
+    }
+
+    let (program, warnings) = analyze(ast, options)?;
+    artifacts.warnings = warnings;
+    let ir = codegen(program, options);
+    artifacts.llvm_ir = Some(ir.clone());
+    if options.emit == Emit::LlvmIr{
+        return Ok(artifacts);
+    }
+
+    build(&ir, options, &mut artifacts).map_err(|err| vec![Diagnostic::error(err)])?;
+    Ok(artifacts)
+}
+
+/// Counts the builds this process has started, see `build`.
+static BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+/// Builds `ir` in a directory of its own and removes that directory again,
+/// fills in the assembly and the file clang wrote.
+fn build(ir: &str, options: &Options, artifacts: &mut Artifacts) -> Result<(), String>{
+    // every build gets its own directory, so builds running side by side
+    // do not overwrite each other's runtime and intermediate files
+    let build = BUILDS.fetch_add(1, Ordering::Relaxed);
+    let build_dir = options.build_dir.join(format!("{}-{}-{build}", options.name, process::id()));
+    let result = build_in(&build_dir, ir, options).and_then(|output| {
+        if options.emit == Emit::Asm{
+            let asm = fs::read_to_string(&output).map_err(|err| format!("cannot read {}: {}", output.display(), err))?;
+            artifacts.asm = Some(asm);
+        }
+        // without `-o` the assembly only lives in the build directory
+        artifacts.output = Some(output).filter(|output| !output.starts_with(&build_dir));
+        Ok(())
+    });
+    // a build directory that is already gone, or was never created, is fine
+    let _ = fs::remove_dir_all(&build_dir);
+    result
+}
+
+/// Links `ir` against the runtime and hands the result to clang, returns the file clang wrote.
+fn build_in(build_dir: &Path, ir: &str, options: &Options) -> Result<PathBuf, String>{
+    let toolchain = Toolchain::discover()?;
+    let name = &options.name;
+    fs::create_dir_all(build_dir)
+        .map_err(|err| format!("cannot create build directory {}: {}", build_dir.display(), err))?;
+    let runtime = runtime::write_runtime(build_dir, &options.target_triple)
+        .map_err(|err| format!("cannot write runtime library to {}: {}", build_dir.display(), err))?;
+    let ir_file = build_dir.join(format!("{name}.ll"));
+    let linked_file = build_dir.join(format!("{name}.bc"));
+
+    fs::write(&ir_file, ir).map_err(|err| format!("cannot write {}: {}", ir_file.display(), err))?;
+
+    let link_status = Command::new(&toolchain.llvm_link.path)
+        .args(&runtime)
+        .arg(&ir_file)
+        .arg("-o")
+        .arg(&linked_file)
+        .status()
+        .map_err(|err| format!("cannot run {}: {}", toolchain.llvm_link.path.display(), err))?;
+
+    if !link_status.success() {
+        return Err("link error.".to_string());
+    }
+
+    let (flag, default_output) = match options.emit {
+        Emit::Asm => (Some("-S"), build_dir.join(format!("{name}.s"))),
+        Emit::Obj => (Some("-c"), PathBuf::from(format!("{name}.o"))),
+        _ => (None, PathBuf::from(target::default_exe_name(&options.target_triple))),
+    };
+    let output_file = options.output.clone().unwrap_or(default_output);
+
+    let compile_status = Command::new(&toolchain.clang.path)
+        .arg("-Wno-override-module")
+        .arg(options.opt_level.clang_flag())
+        .arg(format!("--target={}", options.target_triple))
+        .args(flag)
+        .arg("-o")
+        .arg(&output_file)
+        .arg(&linked_file)
+        .status()
+        .map_err(|err| format!("cannot run {}: {}", toolchain.clang.path.display(), err))?;
+
+    if !compile_status.success() {
+        return Err("compilation failed.".to_string());
+    }
+    Ok(output_file)
+}
+
+/// The base name intermediate files get for `path`.
+pub fn file_stem(path: &Path) -> String{
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("output")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compile_to_ir(){
+        let options = Options { emit: Emit::LlvmIr, ..Options::default() };
+        let artifacts = compile_str("i32 two = 2; sub twice(i32 x) -> i32 { ret x * @two; }", &options).unwrap();
+        assert!(artifacts.ast.is_some());
+        let ir = artifacts.llvm_ir.unwrap();
+        assert!(ir.contains("@ika_u_twice("));
+        assert!(ir.contains("= constant i32 2"));
+        assert!(artifacts.output.is_none());
+    }
+
+    #[test]
+    fn returns_in_ir(){
+        let options = Options { emit: Emit::LlvmIr, ..Options::default() };
+        let ir = compile_str("sub one() -> i32 { ret 1; } sub nothing() { ret; }", &options).unwrap().llvm_ir.unwrap();
+        let body = |symbol: &str| {
+            let start = ir.find(&format!("@{symbol}(")).unwrap();
+            ir[start..start + ir[start..].find("}\n").unwrap()].to_string()
+        };
+        let one = body("ika_u_one");
+        assert!(one.contains("\tret i32 %t"));
+        // sema proved every path returns, so the end of a non-void body is never reached
+        assert!(one.ends_with("\tunreachable\n"));
+        let nothing = body("ika_u_nothing");
+        assert!(nothing.contains("entry:\n\tret void\n"));
+        assert!(!nothing.contains("unreachable"));
+    }
+
+    #[test]
+    fn target_triple_in_ir(){
+        let options = Options { emit: Emit::LlvmIr, target_triple: "aarch64-apple-darwin".to_string(), ..Options::default() };
+        let ir = compile_str("sub main() {}", &options).unwrap().llvm_ir.unwrap();
+        assert!(ir.starts_with("target triple = \"aarch64-apple-darwin\"\n"));
+    }
+
+    #[test]
+    fn user_subs_are_mangled(){
+        // `write` is also the libc function the runtime prints with
+        let options = Options { emit: Emit::LlvmIr, ..Options::default() };
+        let ir = compile_str("sub write(str s) { echo(s); } sub main() { write(\"hi\"); }", &options).unwrap().llvm_ir.unwrap();
+        assert!(ir.contains("define void @ika_u_write(%str"));
+        assert!(ir.contains("call void @ika_u_write("));
+        assert!(ir.contains("call void @ika_u_main()"));
+        assert!(!ir.contains("@write("));
+    }
+
+    #[test]
+    fn block_scopes(){
+        // after the if block `g` is the global again, not the block's local
+        let options = Options { emit: Emit::LlvmIr, ..Options::default() };
+        let source = "i32 g = 1; sub main(){ if true { i32 g = 2; println(g); } println(g); }";
+        let ir = compile_str(source, &options).unwrap().llvm_ir.unwrap();
+        assert!(ir.contains("load i32, ptr @g0"));
+    }
+
+    #[test]
+    fn compilations_are_independent(){
+        let source = "sub main() { i32 x = 1; }";
+        assert!(check(source, &Options::default()).is_ok());
+        assert!(check(source, &Options::default()).is_ok());
+        assert!(check("sub other() { x = 2; }", &Options::default()).is_err());
+    }
+
+    #[test]
+    fn truncated_input(){
+        let options = Options { emit: Emit::LlvmIr, ..Options::default() };
+        for source in ["sub", "sub main(", "sub main() {", "i32 x = 1 +"]{
+            assert_eq!(compile_str(source, &options).unwrap_err(), vec![Diagnostic::error("unexpected end of input")]);
+        }
+        let err = compile_str("sub main() { echo(\"x\")", &options).unwrap_err();
+        assert_eq!(err, vec![Diagnostic::error("Expected 'SEMICOLON' \";\", found the end of input")]);
+        assert_eq!(lex("i32 x = $;").unwrap_err(), vec![Diagnostic::error("invalid token `$` at 1:9")]);
+    }
+
+    #[test]
+    fn diagnostics(){
+        let err = check("sub wrong() { i32 x = \"a\"; }", &Options::default()).unwrap_err();
+        assert_eq!(err, vec![Diagnostic::error("cannot assign str type to i32 type")]);
+
+        let (_, warnings) = check("sub early() -> i32 { ret 1; echo(\"x\"); }", &Options::default()).unwrap();
+        assert_eq!(warnings, vec![Diagnostic::warning("unreachable code in early [unreachable_code]")]);
+    }
+}
@@ -0,0 +1,85 @@
+//! Lints are warnings about code that is legal but probably not meant. Each
+//! one warns unless `-A` allows it, and `#[allow(...)]` turns it off for a
+//! single `sub` or variable.
+
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Lint {
+    /// A variable that is never read
+    #[value(name = "unused_variables")]
+    UnusedVariables,
+    /// A parameter the function never uses
+    #[value(name = "unused_parameters")]
+    UnusedParameters,
+    /// A function that `main` never calls, directly or through other functions
+    #[value(name = "dead_code")]
+    DeadCode,
+    /// A local with the same name as a global, which then needs `@`
+    #[value(name = "shadowing")]
+    Shadowing,
+    /// Statements after one that always returns or exits
+    #[value(name = "unreachable_code")]
+    UnreachableCode,
+}
+
+impl Lint {
+    pub fn name(self) -> &'static str{
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::UnusedParameters => "unused_parameters",
+            Lint::DeadCode => "dead_code",
+            Lint::Shadowing => "shadowing",
+            Lint::UnreachableCode => "unreachable_code",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint>{
+        Lint::value_variants().iter().copied().find(|lint| lint.name() == name)
+    }
+}
+
+/// Which lints are reported. Every lint warns by default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LintLevels {
+    allowed: Vec<Lint>,
+}
+
+impl LintLevels {
+    pub fn allow(&mut self, lint: Lint){
+        if !self.allowed.contains(&lint){
+            self.allowed.push(lint);
+        }
+    }
+
+    pub fn warn(&mut self, lint: Lint){
+        self.allowed.retain(|allowed| *allowed != lint);
+    }
+
+    pub fn is_allowed(&self, lint: Lint) -> bool{
+        self.allowed.contains(&lint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip(){
+        for lint in Lint::value_variants(){
+            assert_eq!(Lint::from_name(lint.name()), Some(*lint));
+        }
+        assert_eq!(Lint::from_name("unused"), None);
+    }
+
+    #[test]
+    fn levels(){
+        let mut levels = LintLevels::default();
+        assert!(!levels.is_allowed(Lint::DeadCode));
+        levels.allow(Lint::DeadCode);
+        assert!(levels.is_allowed(Lint::DeadCode));
+        levels.warn(Lint::DeadCode);
+        assert!(!levels.is_allowed(Lint::DeadCode));
+    }
+}
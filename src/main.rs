@@ -1,152 +1,323 @@
-mod lexer;
-mod parser;
-mod sema;
-mod io;
-mod codegen;
-mod codegen_lib;
-
-use clap::{Parser as cp};
-use std::process::{Command, exit};
-use std::env;
-use codegen::Codegen;
-use lexer::{LEXER, tokenization,Token,TokenType};
-use parser::{Parser};
-use sema::{lib_insert_symbol, SymbolTable, SYMBOL_TABLES};
-use io::{read_fs};
-
-#[derive(cp)]
-#[command(name = "ika")]
-#[command(about = "A simple compiler for ika ")]
-struct Cli {
-    /// Print the AST
-    #[arg(short = 'a', long = "ast")]
-    show_ast: bool,
-
-    ///Specify the output file name, the default is a.out
-    #[arg(short, long)]
-    output: Option<String>,
-
-    ///Print the source code
-    #[arg(short = 's', long = "source")]
-    show_source: bool,
-
-    /// Print tokens
-    #[arg(short = 't', long = "tokens")]
-    show_tokens: bool,
-
-    ///ika file, like xxx.ika
-    #[arg(required = true)]
-    input: String,
-}
-
-pub struct SrcInfo{
-   target_triple:String,
-}
-
-fn main() {
-   
-    let cli = Cli::parse();
-
-    let os = env::consts::OS;
-    
-    let tt = match os {
-        "windows" => "x86_64-pc-windows-msvc".to_string(),
-        "linux" => "x86_64-unknown-linux-gnu".to_string(),
-        "macos" => "x86_64-apple-darwin".to_string(),
-        "freebsd" => "x86_64-unknown-freebsd".to_string(),
-        _ => "x86_64-unknown-linux-gnu".to_string(), 
-    };
-
-    let outfile_name= match os {
-        "windows" => "out.exe".to_string(),
-        "linux" => "a.out".to_string(),
-        _ => "a.out".to_string(),
-    };
-    
-    let src_info = SrcInfo{
-        target_triple: tt
-    };
-
-
-    
-    let input_file = &cli.input;
-    let output_file = cli.output.unwrap_or_else(|| outfile_name);
-
-    let content = read_fs(input_file);
-    let input = content.as_str();
-    
-    if cli.show_source{
-        println!("{input}");
-    }
-   
-    
-    let mut lexer = LEXER::new(input);
-    let mut tokens = Vec::new();
-    tokens = tokenization(&mut lexer).unwrap();
-    
-    
-    tokens.push(Token {
-        token_type: TokenType::EOF,
-        value: String::new(),
-    });
-    
-    if cli.show_tokens{
-        println!("{:?}", tokens);
-    }
-    println!("{:?}", tokens);
-    let mut parser = Parser::new(tokens.clone());
-    let mut codegen = Codegen::new();
-
-    lib_insert_symbol();
-    
-    match parser.parse_program() {
-        
-        Ok(ast) => {
-
-            if cli.show_ast{
-                println!("{:#?}", ast);
-            }
-            
-            let out = codegen.generate_code(ast, src_info).clone();
-           
-
-            std::fs::write(".\\target\\output.ll", out).expect("Unable to write file");
-
-            let compile_status = Command::new("llvm-link")
-                .arg(".\\lib\\base.ll")
-                .arg(".\\lib\\lib_for_linux.ll") 
-                .arg(".\\lib\\lib_for_windows.ll")
-                .arg(".\\target\\output.ll")
-                .arg("-o")
-                .arg(".\\target\\linked.ll")
-                .status()
-                .expect("Failed to run llvm-link");
-
-            if compile_status.success() {
-                println!("Link successful.");         
-            } else {
-                eprintln!("link error.");
-                exit(1);
-            }
-
-            let compile_status2 = Command::new("clang")
-                .arg("-Wno-override-module")
-                .arg("-o") 
-                .arg(&output_file)
-                .arg(".\\target\\linked.ll")
-                .status()
-                .expect("Failed to run clang");
-
-            if compile_status2.success() {
-                println!("Compilation successful. Executable: {}", output_file);         
-            } else {
-                eprintln!("Error during compilation.");
-                exit(1);
-            }
-        },
-        Err(err) => eprintln!("Error: {}", err),
-    }
-    
-    
-
-}
\ No newline at end of file
+mod io;
+
+use clap::{Args, Parser as cp, Subcommand};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::process::{self, Command, ExitStatus, exit};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ika::{compile_str, check, lex, target, toolchain, Diagnostic, Emit, OptLevel, Options};
+use ika::lint::{Lint, LintLevels};
+use io::{read_fs};
+
+#[derive(cp)]
+#[command(name = "ika")]
+#[command(about = "A simple compiler for ika ")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    /// Print the AST
+    #[arg(short = 'a', long = "ast")]
+    show_ast: bool,
+
+    ///Specify the output file name. Text output goes to stdout by default, an object file
+    ///to <input>.o and an executable to a.out
+    #[arg(short, long)]
+    output: Option<String>,
+
+    ///Print the source code
+    #[arg(short = 's', long = "source")]
+    show_source: bool,
+
+    /// Print tokens
+    #[arg(short = 't', long = "tokens")]
+    show_tokens: bool,
+
+    ///What to produce
+    #[arg(long, value_enum, default_value = "exe")]
+    emit: Emit,
+
+    ///Target triple to compile for, the default is the host
+    #[arg(long)]
+    target: Option<String>,
+
+    #[command(flatten)]
+    build: BuildArgs,
+
+    ///List the supported targets
+    #[arg(long = "print-targets")]
+    print_targets: bool,
+
+    ///ika file, like xxx.ika
+    #[arg(required_unless_present = "print_targets")]
+    input: Option<String>,
+}
+
+#[derive(Args)]
+struct BuildArgs {
+    ///Optimization level: 0, 1, 2, 3 or s
+    #[arg(short = 'O', value_enum, default_value = "0")]
+    opt_level: OptLevel,
+
+    ///Directory for intermediate files, the default is `ika` in the system temp directory
+    #[arg(long = "build-dir")]
+    build_dir: Option<PathBuf>,
+
+    #[command(flatten)]
+    check: CheckArgs,
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    ///Report a lint, this wins over -A for the same lint
+    #[arg(short = 'W', value_name = "LINT", value_enum)]
+    warn: Vec<Lint>,
+
+    ///Do not report a lint
+    #[arg(short = 'A', value_name = "LINT", value_enum)]
+    allow: Vec<Lint>,
+
+    ///Start locals that may be read before they are assigned as zero, with a warning
+    ///instead of an error
+    #[arg(long = "zero-init")]
+    zero_init: bool,
+}
+
+impl CheckArgs {
+    fn apply(&self, options: Options) -> Options{
+        let mut lints = LintLevels::default();
+        for lint in &self.allow{
+            lints.allow(*lint);
+        }
+        for lint in &self.warn{
+            lints.warn(*lint);
+        }
+        Options { lints, zero_init: self.zero_init, ..options }
+    }
+}
+
+impl BuildArgs {
+    fn options(&self, input_file: &str, emit: Emit) -> Options{
+        let defaults = Options::default();
+        self.check.apply(Options {
+            opt_level: self.opt_level,
+            emit,
+            build_dir: self.build_dir.clone().unwrap_or(defaults.build_dir),
+            name: ika::file_stem(Path::new(input_file)),
+            ..defaults
+        })
+    }
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Compile a program for the host and run it
+    Run {
+        #[command(flatten)]
+        build: BuildArgs,
+
+        ///ika file, like xxx.ika
+        input: String,
+
+        ///Arguments for the program
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Check a program for errors without generating code
+    Check {
+        #[command(flatten)]
+        check: CheckArgs,
+
+        ///ika file, like xxx.ika
+        input: String,
+    },
+    /// Report the LLVM tools ika would use
+    Doctor,
+}
+
+fn fail(msg: String) -> ! {
+    eprintln!("Error: {}", msg);
+    exit(1);
+}
+
+fn warn(input_file: &str, diagnostics: &[Diagnostic]){
+    for diagnostic in diagnostics{
+        eprintln!("{}: {}", input_file, diagnostic);
+    }
+}
+
+fn report(input_file: &str, diagnostics: Vec<Diagnostic>) -> !{
+    warn(input_file, &diagnostics);
+    exit(1);
+}
+
+/// Writes text output to the `-o` file, or to stdout when there is none.
+fn write_text(output: Option<&str>, text: &str){
+    match output {
+        Some(path) => {
+            if let Err(err) = fs::write(path, text){
+                fail(format!("cannot write {}: {}", path, err));
+            }
+        },
+        None => print!("{}", text),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String{
+    let mut json = serde_json::to_string_pretty(value).expect("the syntax tree is always serializable");
+    json.push('\n');
+    json
+}
+
+fn read_source(input_file: &str) -> String{
+    match read_fs(input_file) {
+        Ok(content) => content,
+        Err(err) => fail(format!("cannot read {}: {}", input_file, err)),
+    }
+}
+
+fn compile(cli: Cli){
+    if cli.print_targets{
+        for triple in target::TARGETS{
+            println!("{triple}");
+        }
+        return;
+    }
+
+    let input_file = cli.input.as_deref().unwrap_or_default();
+    let mut options = cli.build.options(input_file, cli.emit);
+    if let Some(tt) = &cli.target{
+        options.target_triple = tt.clone();
+    }
+    // assembly is printed unless -o is given, so it stays in the build directory
+    options.output = cli.output.as_ref().map(PathBuf::from);
+
+    let content = read_source(input_file);
+    let input = content.as_str();
+
+    if cli.show_source{
+        println!("{input}");
+    }
+
+    if cli.show_tokens{
+        if let Ok(tokens) = lex(input){
+            println!("{:?}", tokens);
+        }
+    }
+
+    let artifacts = compile_str(input, &options).unwrap_or_else(|err| report(input_file, err));
+    warn(input_file, &artifacts.warnings);
+
+    if cli.show_ast{
+        if let Some(ast) = &artifacts.ast{
+            println!("{:#?}", ast);
+        }
+    }
+
+    let output = cli.output.as_deref();
+    match cli.emit {
+        Emit::Tokens => write_text(output, &to_json(&artifacts.tokens)),
+        Emit::Ast => write_text(output, &to_json(&artifacts.ast)),
+        Emit::LlvmIr => write_text(output, artifacts.llvm_ir.as_deref().unwrap_or_default()),
+        Emit::Asm if output.is_none() => print!("{}", artifacts.asm.unwrap_or_default()),
+        Emit::Exe => {
+            let exe = artifacts.output.unwrap_or_default();
+            println!("Compilation successful. Executable: {}", exe.display());
+        },
+        _ => {},
+    }
+}
+
+/// Builds `input_file` into `<build-dir>/cache`, reusing the binary while neither the
+/// source, the options nor the compiler change, then runs it and exits with its status.
+fn run(build: BuildArgs, input_file: &str, args: Vec<String>) -> !{
+    let mut options = build.options(input_file, Emit::Exe);
+    let content = read_source(input_file);
+
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    options.opt_level.hash(&mut hasher);
+    options.zero_init.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    if let Ok(modified) = env::current_exe().and_then(fs::metadata).and_then(|meta| meta.modified()){
+        modified.hash(&mut hasher);
+    }
+
+    let cache_dir = options.build_dir.join("cache");
+    if let Err(err) = fs::create_dir_all(&cache_dir){
+        fail(format!("cannot create cache directory {}: {}", cache_dir.display(), err));
+    }
+    let stem = &options.name;
+    let exe_name = format!("{stem}-{:016x}{}", hasher.finish(), env::consts::EXE_SUFFIX);
+    let exe = cache_dir.join(exe_name);
+
+    if !exe.exists(){
+        // build next to the cache entry and move it in, so a run never sees half a binary
+        let partial = cache_dir.join(format!("{stem}-{}.partial{}", process::id(), env::consts::EXE_SUFFIX));
+        options.output = Some(partial.clone());
+        match compile_str(&content, &options) {
+            Ok(artifacts) => warn(input_file, &artifacts.warnings),
+            Err(err) => report(input_file, err),
+        }
+        if let Err(err) = fs::rename(&partial, &exe){
+            fail(format!("cannot write {}: {}", exe.display(), err));
+        }
+    }
+
+    match Command::new(&exe).args(args).status() {
+        Ok(status) => exit(exit_code(status)),
+        Err(err) => fail(format!("cannot run {}: {}", exe.display(), err)),
+    }
+}
+
+/// Runs the front end only, so it works without an LLVM toolchain.
+fn check_file(check_args: CheckArgs, input_file: &str) -> !{
+    let content = read_source(input_file);
+    match check(&content, &check_args.apply(Options::default())) {
+        Ok((_, warnings)) => {
+            warn(input_file, &warnings);
+            exit(0)
+        },
+        Err(err) => report(input_file, err),
+    }
+}
+
+fn doctor() -> !{
+    println!("host target: {}", target::host_triple());
+    let mut ok = true;
+    for (name, env_var) in [("clang", "IKA_CLANG"), ("llvm-link", "IKA_LLVM_LINK")]{
+        match toolchain::find_tool(name, env_var) {
+            Ok(tool) => println!("{name}: {} (LLVM {}.{})", tool.path.display(), tool.version.0, tool.version.1),
+            Err(err) => {
+                println!("{name}: {err}");
+                ok = false;
+            },
+        }
+    }
+    exit(if ok { 0 } else { 1 });
+}
+
+/// A program killed by a signal exits like it would from a shell.
+fn exit_code(status: ExitStatus) -> i32{
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal(){
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Commands::Run { build, input, args }) => run(build, &input, args),
+        Some(Commands::Check { check, input }) => check_file(check, &input),
+        Some(Commands::Doctor) => doctor(),
+        None => compile(cli),
+    }
+}
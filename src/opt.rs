@@ -0,0 +1,109 @@
+use crate::tast::{Expr, ExprKind, Function, Item, Program, Stmt, Var};
+use crate::types::Type;
+
+/// Folds `i32` arithmetic and comparisons whose operands are literals.
+/// Anything that would trap or overflow at run time is left for the program to do.
+pub fn fold_constants(program: Program) -> Program{
+    let items = program.items.into_iter().map(|item| match item {
+        Item::Function(func) => Item::Function(Function { body: fold_block(func.body), ..func }),
+        Item::Global(var) => Item::Global(fold_var(var)),
+    }).collect();
+    Program { items, ..program }
+}
+
+fn fold_block(body: Vec<Stmt>) -> Vec<Stmt>{
+    body.into_iter().map(fold_stmt).collect()
+}
+
+fn fold_var(var: Var) -> Var{
+    Var { value: var.value.map(fold_expr), ..var }
+}
+
+fn fold_stmt(stmt: Stmt) -> Stmt{
+    match stmt {
+        Stmt::Var(var) => Stmt::Var(fold_var(var)),
+        Stmt::Assign { name, value } => Stmt::Assign { name, value: fold_expr(value) },
+        Stmt::Expr(expr) => Stmt::Expr(fold_expr(expr)),
+        Stmt::If { cond, then_body, elifs, else_body } => Stmt::If {
+            cond: fold_expr(cond),
+            then_body: fold_block(then_body),
+            elifs: elifs.into_iter().map(|(cond, body)| (fold_expr(cond), fold_block(body))).collect(),
+            else_body: else_body.map(fold_block),
+        },
+        Stmt::While { cond, body } => Stmt::While { cond: fold_expr(cond), body: fold_block(body) },
+        Stmt::Return(expr) => Stmt::Return(expr.map(fold_expr)),
+    }
+}
+
+fn fold_expr(expr: Expr) -> Expr{
+    let fold_all = |exprs: Vec<Expr>| exprs.into_iter().map(fold_expr).collect::<Vec<_>>();
+    let fold_box = |expr: Box<Expr>| Box::new(fold_expr(*expr));
+
+    let kind = match expr.kind {
+        ExprKind::Call { name, args } => ExprKind::Call { name, args: fold_all(args) },
+        ExprKind::Binary { left, op, right } => {
+            let left = fold_box(left);
+            let right = fold_box(right);
+            if let (ExprKind::Int(l), ExprKind::Int(r)) = (&left.kind, &right.kind){
+                if let Some(folded) = fold_int(*l, &op, *r){
+                    return folded;
+                }
+            }
+            ExprKind::Binary { left, op, right }
+        },
+        ExprKind::Index { array, index } => ExprKind::Index { array: fold_box(array), index: fold_box(index) },
+        ExprKind::Slice { string, start, end } => ExprKind::Slice {
+            string: fold_box(string),
+            start: start.map(fold_box),
+            end: end.map(fold_box),
+        },
+        ExprKind::Len(expr) => ExprKind::Len(fold_box(expr)),
+        ExprKind::Interpolation(parts) => ExprKind::Interpolation(fold_all(parts)),
+        leaf => leaf,
+    };
+    Expr { kind, ..expr }
+}
+
+fn fold_int(l: i32, op: &str, r: i32) -> Option<Expr>{
+    let number = |n: Option<i32>| n.map(|n| Expr::new(ExprKind::Int(n), Type::I32));
+    let bool = |b: bool| Some(Expr::new(ExprKind::Bool(b), Type::BOOL));
+    match op {
+        "+" => number(l.checked_add(r)),
+        "-" => number(l.checked_sub(r)),
+        "*" => number(l.checked_mul(r)),
+        "/" => number(l.checked_div(r)),
+        "==" => bool(l == r),
+        "!=" => bool(l != r),
+        "<" => bool(l < r),
+        "<=" => bool(l <= r),
+        ">" => bool(l > r),
+        ">=" => bool(l >= r),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(n: i32) -> Expr{
+        Expr::new(ExprKind::Int(n), Type::I32)
+    }
+
+    #[test]
+    fn folds_nested_arithmetic(){
+        let expr = Expr::infix(Expr::infix(num(2), "*", num(3)), "+", num(4));
+        assert_eq!(fold_expr(expr), num(10));
+        assert_eq!(fold_expr(Expr::infix(num(1), "<", num(2))), Expr::new(ExprKind::Bool(true), Type::BOOL));
+    }
+
+    #[test]
+    fn leaves_traps_alone(){
+        let div = Expr::infix(num(1), "/", num(0));
+        assert_eq!(fold_expr(div.clone()), div);
+        let overflow = Expr::infix(num(2147483647), "+", num(1));
+        assert_eq!(fold_expr(overflow.clone()), overflow);
+        let var = Expr::infix(Expr::new(ExprKind::Variable("x".to_string()), Type::I32), "+", num(1));
+        assert_eq!(fold_expr(var.clone()), var);
+    }
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::target::is_windows;
+
+// The runtime is compiled into the driver so `ika` does not depend on
+// the working directory or on where the repository was checked out.
+const BASE: &str = include_str!("../lib/base.ll");
+const LIB_FOR_LINUX: &str = include_str!("../lib/lib_for_linux.ll");
+const LIB_FOR_WINDOWS: &str = include_str!("../lib/lib_for_windows.ll");
+
+/// The platform half of the runtime: `ika_echo`, `ika_alloc`, `ika_read` and `ika_exit`.
+fn platform_lib(target_triple: &str) -> (&'static str, &'static str){
+    if is_windows(target_triple){
+        ("lib_for_windows.ll", LIB_FOR_WINDOWS)
+    }else{
+        ("lib_for_linux.ll", LIB_FOR_LINUX)
+    }
+}
+
+/// Writes the runtime modules for `target_triple` into `dir` and returns their paths.
+pub fn write_runtime(dir: &Path, target_triple: &str) -> io::Result<Vec<PathBuf>>{
+    let (name, source) = platform_lib(target_triple);
+    let mut paths = vec![];
+    for (name, source) in [("base.ll", BASE), (name, source)]{
+        let path = dir.join(name);
+        fs::write(&path, source)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_by_target(){
+        assert_eq!(platform_lib("x86_64-pc-windows-msvc").0, "lib_for_windows.ll");
+        assert_eq!(platform_lib("x86_64-unknown-linux-gnu").0, "lib_for_linux.ll");
+        assert!(platform_lib("x86_64-unknown-linux-gnu").1.contains("@ika_echo"));
+    }
+}
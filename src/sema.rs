@@ -1,219 +1,1219 @@
-use core::arch;
-use std::collections::HashMap;
-use lazy_static::lazy_static;
-use std::sync::{Arc, Mutex};
-use crate::parser::{ASTNode};
-
-#[derive(Debug,Clone)]
-pub struct SymbolTable{
-    variables: HashMap<String, String>,
-    functions: HashMap<String, Function>,
-}
-
-
-#[derive(Debug,Clone)]
-pub struct Function {
-    pub fn_name: String,
-    pub paras: Vec<(String, String)>,
-    pub ret_type: Option<String>,
-}
-
-impl SymbolTable {
-    pub fn new() -> Self{
-        Self { variables: HashMap::new(), functions: HashMap::new() }
-    }
-
-    pub fn add_variable(&mut self, name: String, ty: String)  {
-        self.variables.insert(name, ty);
-    }
-
-    pub fn add_function(&mut self, name: String, func: Function) {
-        self.functions.insert(name, func);
-    }
-
-    pub fn lookup_variable(&self, name: &str) -> Option<String> {
-        self.variables.get(name).cloned()
-    }
-
-    pub fn lookup_function(&self, name: &str) -> Option<&Function> {
-        self.functions.get(name)
-    }
-
-    pub fn has_variable(&self, name:&str) -> bool {
-        if self.variables.get(name).is_none(){
-            false
-        } else{
-            true
-        }
-    }
-
-    pub fn has_function(&self, name:&str) -> bool {
-        if self.functions.get(name).is_none(){
-            false
-        } else{
-            true
-        }
-    }
-}
-
-#[derive(Debug)]
-pub struct ScopeManager{
-    pub stack: Vec<SymbolTable>
-}
-
-impl ScopeManager{
-    fn new() -> Self{
-        let mut sm = Self { stack: Vec::new() };
-        sm.push_scope();
-        sm
-    }
-
-   
-    pub fn push_scope(&mut self) {
-        self.stack.push(SymbolTable::new());
-    }
-
-    pub fn pop_scope(&mut self) {
-        self.stack.pop();
-    }
-
-    pub fn current_scope_mut(&mut self) -> &mut SymbolTable {
-        self.stack.last_mut().unwrap()
-    }
-
-    pub fn current_scope(&self) -> &SymbolTable {
-        self.stack.last().unwrap()
-    }
-
-    
-
-    pub fn global_scope(&self) -> &SymbolTable {
-        self.stack.first().unwrap()
-    }
-    pub fn global_scope_mut(&mut self) -> &mut SymbolTable {
-        self.stack.first_mut().unwrap()
-    }
-
-    pub fn is_global_scope(&self) -> bool{
-        if self.stack.len() == 1{
-            true
-        } else{
-            false
-        }
-    }
-}
-
-lazy_static!{
-  
-    pub static ref SYMBOL_TABLES: Arc<Mutex<ScopeManager>> = Arc::new(Mutex::new(ScopeManager::new()));
-    
-}
-
-pub fn lib_insert_symbol(){
-    SYMBOL_TABLES.lock().unwrap().global_scope_mut().add_function("echo".to_string(),  Function {
-        fn_name: "echo".to_string(),
-        paras: Vec::from([("str".to_string(), "string".to_string()),( "i32".to_string(), "len".to_string())]),
-        ret_type: None,
-    });
-    SYMBOL_TABLES.lock().unwrap().global_scope_mut().add_function("itos".to_string(),  Function {
-        fn_name: "itos".to_string(),
-        paras: Vec::from([( "i32".to_string(), "num".to_string())]),
-        ret_type: Some("str".to_string()),
-    });
-    SYMBOL_TABLES.lock().unwrap().global_scope_mut().add_function("len".to_string(),  Function {
-        fn_name: "len".to_string(),
-        paras: Vec::from([( "str".to_string(), "string".to_string())]),
-        ret_type: Some("i32".to_string()),
-    });
-}
-
-pub fn get_var(name: String)->String{
-    let sym = SYMBOL_TABLES.lock().unwrap();
-    let info = sym.global_scope().lookup_variable(name.as_str()).unwrap().clone();
-    info
-}
-
-pub fn current_index()->usize{
-    let sym = SYMBOL_TABLES.lock().unwrap();
-    sym.stack.len()-1
-    
-}
-
-pub fn insert_var(name:String, var_type:String){
-    
-    let mut sym = SYMBOL_TABLES.lock().unwrap();
-    sym.current_scope_mut().add_variable(name.clone(), var_type.clone());
-}
-
-pub fn has_var(name: String, scope:&mut usize) -> bool{
-    let sym = SYMBOL_TABLES.lock().unwrap();
-    loop {
-        let st = sym.stack[*scope].clone();
-        let b = st.has_variable(name.as_str());
-        if b{
-            return true;
-        }else if *scope == 0{
-            break;
-        }else {
-            *scope -= 1;
-        }
-        
-    }
-    false
-}
-pub fn get_ty(name: String, scope:&mut usize) -> String{
-    let sym = SYMBOL_TABLES.lock().unwrap();
-    loop {
-        let st = sym.stack[*scope].clone();
-        let b = st.has_variable(name.as_str());
-        if b{
-            return sym.stack[*scope].lookup_variable(name.as_str()).unwrap();
-        }else if *scope == 0{
-            break;
-        }else {
-            *scope -= 1;
-        }
-        
-    }
-    "".to_string()
-}
-
-
-pub fn get_fun(name: String)->Function{
-    let sym = SYMBOL_TABLES.lock().unwrap();
-    let info = sym.global_scope().lookup_function(name.as_str()).unwrap().clone();
-    let a = info.fn_name.clone();
-    let b = info.paras.clone();
-    let c = info.ret_type.clone();
-    Function{
-        fn_name: a,
-        paras: b,
-        ret_type:c
-    } 
-}
-
-#[cfg(test)]
-mod tests{
-    use super::*;
-
-    #[test]
-    fn add_has_value(){
-        let mut st = SymbolTable::new();
-        st.add_variable("a".to_string(), "i32".to_string());
-        let res1 = st.has_variable("a");
-        let res2 = st.has_variable("b");
-
-        assert_eq!(res1, true);
-        assert_eq!(res2, false);
-    }
-
-    #[test]
-    fn lookup(){
-        let mut st = SymbolTable::new();
-        st.add_variable("a".to_string(), "i32".to_string());
-        let res1 = st.lookup_variable("a");
-        let res2 = st.lookup_variable("b");
-        assert_eq!(res1, Some("i32".to_string()));
-        assert_eq!(res2, None); 
-    }
-}
\ No newline at end of file
+use std::collections::{HashMap, HashSet};
+use crate::consteval;
+use crate::lint::{Lint, LintLevels};
+use crate::parser::{ASTNode, Binding};
+use crate::tast::{self, Expr, ExprKind, Item, Program, Stmt, Var};
+use crate::types::{Type, Types};
+
+#[derive(Debug,Clone)]
+pub struct SymbolTable{
+    variables: HashMap<String, Variable>,
+    /// Variable names in the order they were defined, so lints come out in source order
+    order: Vec<String>,
+    functions: HashMap<String, Function>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VarKind {
+    Global,
+    Local,
+    Param,
+}
+
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub ty: Type,
+    pub kind: VarKind,
+    /// Whether anything reads it, assigning to it does not count
+    pub used: bool,
+    /// Lints turned off where it was defined
+    pub allow: Vec<Lint>,
+    pub binding: Binding,
+    /// The value of a `const`, once it is computed
+    pub value: Option<Expr>,
+}
+
+impl Variable {
+    pub fn new(ty: Type, kind: VarKind, allow: Vec<Lint>) -> Self{
+        Self { ty, kind, used: false, allow, binding: Binding::Immutable, value: None }
+    }
+}
+
+
+#[derive(Debug,Clone)]
+pub struct Function {
+    pub fn_name: String,
+    pub paras: Vec<(Type, String)>,
+    pub ret_type: Type,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self{
+        Self { variables: HashMap::new(), order: Vec::new(), functions: HashMap::new() }
+    }
+
+    pub fn add_variable(&mut self, name: String, var: Variable)  {
+        self.order.push(name.clone());
+        self.variables.insert(name, var);
+    }
+
+    pub fn add_function(&mut self, name: String, func: Function) {
+        self.functions.insert(name, func);
+    }
+
+    pub fn lookup_variable(&self, name: &str) -> Option<Type> {
+        self.variables.get(name).map(|var| var.ty)
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<&Variable> {
+        self.variables.get(name)
+    }
+
+    pub fn get_variable_mut(&mut self, name: &str) -> Option<&mut Variable> {
+        self.variables.get_mut(name)
+    }
+
+    /// Marks `name` as read and returns its type.
+    pub fn read_variable(&mut self, name: &str) -> Option<Type> {
+        let var = self.variables.get_mut(name)?;
+        var.used = true;
+        Some(var.ty)
+    }
+
+    /// The variables in the order they were defined.
+    pub fn variables(&self) -> impl Iterator<Item = (&String, &Variable)> {
+        self.order.iter().map(|name| (name, &self.variables[name]))
+    }
+
+    pub fn lookup_function(&self, name: &str) -> Option<&Function> {
+        self.functions.get(name)
+    }
+
+    pub fn has_variable(&self, name:&str) -> bool {
+        self.variables.contains_key(name)
+    }
+
+    pub fn has_function(&self, name:&str) -> bool {
+        self.functions.contains_key(name)
+    }
+}
+
+#[derive(Debug)]
+pub struct ScopeManager{
+    pub stack: Vec<SymbolTable>
+}
+
+impl ScopeManager{
+    fn new() -> Self{
+        let mut sm = Self { stack: Vec::new() };
+        sm.push_scope();
+        sm
+    }
+
+   
+    pub fn push_scope(&mut self) {
+        self.stack.push(SymbolTable::new());
+    }
+
+    pub fn pop_scope(&mut self) -> Option<SymbolTable> {
+        self.stack.pop()
+    }
+
+    pub fn current_scope_mut(&mut self) -> &mut SymbolTable {
+        self.stack.last_mut().unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn current_scope(&self) -> &SymbolTable {
+        self.stack.last().unwrap()
+    }
+
+    
+
+    pub fn global_scope(&self) -> &SymbolTable {
+        self.stack.first().unwrap()
+    }
+    pub fn global_scope_mut(&mut self) -> &mut SymbolTable {
+        self.stack.first_mut().unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_global_scope(&self) -> bool{
+        self.stack.len() == 1
+    }
+
+    pub fn insert_var(&mut self, name: String, var: Variable){
+        self.current_scope_mut().add_variable(name, var);
+    }
+
+    /// The innermost variable called `name`.
+    pub fn get_var(&self, name: &str) -> Option<&Variable>{
+        self.stack.iter().rev().find_map(|scope| scope.get_variable(name))
+    }
+
+    /// Marks the innermost variable called `name` as read and returns its type.
+    pub fn read_var(&mut self, name: &str) -> Option<Type>{
+        self.stack.iter_mut().rev().find_map(|scope| scope.read_variable(name))
+    }
+
+    pub fn has_var(&self, name: &str) -> bool{
+        self.stack.iter().any(|scope| scope.has_variable(name))
+    }
+
+    /// Whether a scope inside a function defines `name`, locals may shadow globals
+    /// but not each other.
+    pub fn has_local(&self, name: &str) -> bool{
+        self.stack.iter().skip(1).any(|scope| scope.has_variable(name))
+    }
+
+    pub fn get_fun(&self, name: &str) -> Option<Function>{
+        self.global_scope().lookup_function(name).cloned()
+    }
+}
+
+/// Per-compilation state. Every program is checked against its own `Context`,
+/// so compilations in one process do not see each other's symbols.
+#[derive(Debug)]
+pub struct Context{
+    pub scopes: ScopeManager,
+    pub types: Types,
+    /// Warnings found so far, errors stop the check instead
+    pub warnings: Vec<String>,
+    pub lints: LintLevels,
+    /// Zero locals that may be read before they are assigned and warn,
+    /// instead of rejecting the program
+    pub zero_init: bool,
+}
+
+impl Context{
+    /// A context whose global scope already holds the runtime library functions.
+    pub fn new() -> Self{
+        let mut scopes = ScopeManager::new();
+        for func in lib_functions(){
+            scopes.global_scope_mut().add_function(func.fn_name.clone(), func);
+        }
+        Self { scopes, types: Types::new(), warnings: Vec::new(), lints: LintLevels::default(), zero_init: false }
+    }
+
+    /// Warns about `lint` unless it is allowed for the whole program or by `allow`.
+    fn lint(&mut self, lint: Lint, allow: &[Lint], message: String){
+        if !self.lints.is_allowed(lint) && !allow.contains(&lint){
+            self.warnings.push(format!("{message} [{}]", lint.name()));
+        }
+    }
+}
+
+impl Default for Context{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Functions implemented by the runtime library in `lib/`. Both the checker's
+/// symbol table and the codegen declarations are built from this list.
+pub fn lib_functions() -> Vec<Function>{
+    let lib = [
+        ("echo", vec![(Type::STR, "string")], Type::VOID),
+        ("itos", vec![(Type::I32, "num")], Type::STR),
+        ("len", vec![(Type::STR, "string")], Type::I32),
+        ("read_line", vec![], Type::STR),
+        ("read_int", vec![], Type::I32),
+        ("exit", vec![(Type::I32, "code")], Type::VOID),
+        ("print_i32", vec![(Type::I32, "value")], Type::VOID),
+        ("print_bool", vec![(Type::BOOL, "value")], Type::VOID),
+        ("print_str", vec![(Type::STR, "value")], Type::VOID),
+        ("print_char", vec![(Type::CHAR, "value")], Type::VOID),
+        ("print_f64", vec![(Type::F64, "value")], Type::VOID),
+        ("print_newline", vec![], Type::VOID),
+    ];
+
+    lib.into_iter().map(|(name, paras, ret_type)| Function {
+        fn_name: name.to_string(),
+        paras: paras.into_iter().map(|(ty, name)| (ty, name.to_string())).collect(),
+        ret_type,
+    }).collect()
+}
+
+/// Picks the runtime function that `print`/`println` call for one argument.
+pub fn print_function(ty: Type) -> Option<&'static str>{
+    match ty {
+        Type::I32 => Some("print_i32"),
+        Type::BOOL => Some("print_bool"),
+        Type::STR => Some("print_str"),
+        Type::CHAR => Some("print_char"),
+        Type::F64 => Some("print_f64"),
+        _ => None,
+    }
+}
+
+/// Resolves every name in the parser's tree and gives every expression a
+/// type, producing the typed AST that `Codegen` lowers.
+pub fn check_program(ast: ASTNode, ctx: &mut Context) -> Result<Program, String>{
+    let ASTNode::Program(statements) = ast else {
+        return Err("expected a program".to_string());
+    };
+    check_main(&statements)?;
+
+    let mut checker = Checker {
+        ctx,
+        function: None,
+        allow: Vec::new(),
+        calls: HashMap::new(),
+        defined: Vec::new(),
+        unassigned: HashSet::new(),
+        global_inits: HashMap::new(),
+        evaluating: Vec::new(),
+    };
+    // every top-level name is known before any body is checked, so
+    // definitions can come in any order
+    for stat in &statements{
+        checker.declare_item(stat)?;
+    }
+    // globals are computed first, so constants have their values in every body
+    let (functions, globals): (Vec<_>, Vec<_>) = statements.into_iter().enumerate().partition(|(_, stat)| is_function(stat));
+    let mut items = Vec::new();
+    for (i, stat) in globals{
+        items.push((i, checker.check_item(stat)?));
+    }
+    for (_, item) in &mut items{
+        if let Item::Global(var) = item{
+            if var.value.is_some(){
+                var.value = Some(checker.eval_global(&var.name)?);
+            }
+        }
+    }
+    for (i, stat) in functions{
+        items.push((i, checker.check_item(stat)?));
+    }
+    items.sort_by_key(|(i, _)| *i);
+    let items = items.into_iter().map(|(_, item)| item).collect();
+    let globals = checker.ctx.scopes.global_scope().clone();
+    checker.lint_unused(&globals);
+    checker.lint_dead_code();
+    Ok(Program { items, types: checker.ctx.types.clone() })
+}
+
+/// `main` becomes the process entry point, so it may only take the
+/// command-line arguments and may only return an exit code.
+fn check_main(statements: &[ASTNode]) -> Result<(), String>{
+    for mut stat in statements{
+        while let ASTNode::Attribute { item, .. } = stat{
+            stat = item;
+        }
+        if let ASTNode::FunctionDefinition { fn_name, parameters, ret_type, .. } = stat{
+            if fn_name != "main"{
+                continue;
+            }
+            let paras_ok = parameters.is_empty() || (parameters.len() == 1 && parameters[0].0 == "str[]");
+            let ret_ok = ret_type.is_none() || ret_type.as_deref() == Some("i32");
+            if !paras_ok || !ret_ok{
+                return Err("main must be declared as `sub main()` or `sub main(str[] args)`, optionally returning i32".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+struct Checker<'a>{
+    ctx: &'a mut Context,
+    /// The function whose body is being checked
+    function: Option<Function>,
+    /// Lints turned off by the `#[allow(...)]` attributes around the current item
+    allow: Vec<Lint>,
+    /// The functions each function calls
+    calls: HashMap<String, Vec<String>>,
+    /// Functions defined by the program and the lints allowed on each
+    defined: Vec<(String, Vec<Lint>)>,
+    /// Locals that are not assigned on every path to the current statement
+    unassigned: HashSet<String>,
+    /// The checked initialiser of each global that has one
+    global_inits: HashMap<String, Expr>,
+    /// Globals whose initialisers are being computed, to catch cycles
+    evaluating: Vec<String>,
+}
+
+impl Checker<'_>{
+    fn name(&self, ty: Type) -> String{
+        self.ctx.types.name(ty)
+    }
+
+    /// Runs `f` with `lints` turned off.
+    fn with_allowed<T>(&mut self, lints: Vec<Lint>, f: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String>{
+        let len = self.allow.len();
+        self.allow.extend(lints);
+        let res = f(self);
+        self.allow.truncate(len);
+        res
+    }
+
+    /// Pops the innermost scope, warning about what it defined but never read.
+    /// Nothing is reported when `res` is an error.
+    fn pop_scope<T>(&mut self, res: Result<T, String>) -> Result<T, String>{
+        let scope = self.ctx.scopes.pop_scope().expect("a pushed scope");
+        for (name, _) in scope.variables(){
+            self.unassigned.remove(name);
+        }
+        let res = res?;
+        self.lint_unused(&scope);
+        Ok(res)
+    }
+
+    fn lint_unused(&mut self, scope: &SymbolTable){
+        let fn_name = self.function.as_ref().map(|func| func.fn_name.clone()).unwrap_or_default();
+        for (name, var) in scope.variables(){
+            if var.used{
+                continue;
+            }
+            let (lint, message) = match var.kind {
+                VarKind::Global => (Lint::UnusedVariables, format!("unused global '{name}'")),
+                VarKind::Local => (Lint::UnusedVariables, format!("unused variable '{name}' in {fn_name}")),
+                VarKind::Param => (Lint::UnusedParameters, format!("unused parameter '{name}' of {fn_name}")),
+            };
+            self.ctx.lint(lint, &var.allow, message);
+        }
+    }
+
+    fn lint_shadowing(&mut self, name: &str){
+        if self.ctx.scopes.global_scope().has_variable(name){
+            let fn_name = self.function.as_ref().map(|func| func.fn_name.as_str()).unwrap_or_default();
+            let message = format!("local '{name}' in {fn_name} shadows a global, use @{name} for the global");
+            self.ctx.lint(Lint::Shadowing, &self.allow, message);
+        }
+    }
+
+    /// Warns about every function `main` cannot reach. Without a `main` any
+    /// function may be an entry point, so nothing is reported.
+    fn lint_dead_code(&mut self){
+        if !self.defined.iter().any(|(name, _)| name == "main"){
+            return;
+        }
+        let mut reached = HashSet::new();
+        let mut todo = vec!["main".to_string()];
+        while let Some(name) = todo.pop(){
+            if reached.insert(name.clone()){
+                todo.extend(self.calls.get(&name).into_iter().flatten().cloned());
+            }
+        }
+        for (name, allow) in std::mem::take(&mut self.defined){
+            if !reached.contains(&name){
+                self.ctx.lint(Lint::DeadCode, &allow, format!("function '{name}' is never called from main"));
+            }
+        }
+    }
+
+    /// Adds the signature of a top-level `sub` or the type of a global to the
+    /// global scope.
+    fn declare_item(&mut self, node: &ASTNode) -> Result<(), String>{
+        match node {
+            ASTNode::Attribute { name, args, item } => {
+                let lints = attribute_lints(name, args, item)?;
+                return self.with_allowed(lints, |checker| checker.declare_item(item));
+            }
+            ASTNode::FunctionDefinition { fn_name, parameters, ret_type, .. } => {
+                if self.ctx.scopes.global_scope().has_function(fn_name.as_str()) {
+                    return Err(format!("Function '{}' is already defined", fn_name));
+                }
+                // globals and functions share one namespace in the generated code
+                if self.ctx.scopes.global_scope().has_variable(fn_name) {
+                    return Err(format!("'{}' is already defined as a variable", fn_name));
+                }
+                let mut paras = Vec::new();
+                for (ty, name) in parameters{
+                    if paras.iter().any(|(_, para)| para == name){
+                        return Err(format!("Parameter '{}' is already defined", name));
+                    }
+                    paras.push((self.ctx.types.resolve(ty)?, name.clone()));
+                }
+                let ret_type = match ret_type {
+                    Some(ty) => self.ctx.types.resolve(ty)?,
+                    None => Type::VOID,
+                };
+                let func = Function { fn_name: fn_name.clone(), paras, ret_type };
+                self.ctx.scopes.global_scope_mut().add_function(fn_name.clone(), func);
+            }
+            ASTNode::VariableDefinition { var_type, identifier, binding, .. } => {
+                if self.ctx.scopes.has_var(identifier){
+                    return Err(format!("Variable '{}' is already defined", identifier));
+                }
+                if self.ctx.scopes.global_scope().has_function(identifier) {
+                    return Err(format!("'{}' is already defined as a function", identifier));
+                }
+                let ty = self.ctx.types.resolve(var_type)?;
+                let mut var = Variable::new(ty, VarKind::Global, self.allow.clone());
+                var.binding = *binding;
+                self.ctx.scopes.global_scope_mut().add_variable(identifier.clone(), var);
+            }
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn check_item(&mut self, node: ASTNode) -> Result<Item, String>{
+        match node {
+            ASTNode::Attribute { name, args, item } => {
+                let lints = attribute_lints(&name, &args, &item)?;
+                self.with_allowed(lints, |checker| checker.check_item(*item))
+            }
+            ASTNode::FunctionDefinition { fn_name, body, .. } =>
+                self.check_function(fn_name, body).map(Item::Function),
+            ASTNode::VariableDefinition { identifier, var_value, binding, .. } => {
+                let ty = self.ctx.scopes.global_scope().lookup_variable(&identifier).unwrap();
+                let value = self.check_init(ty, var_value)?;
+                match &value {
+                    Some(value) => {
+                        self.global_inits.insert(identifier.clone(), value.clone());
+                    }
+                    None => needs_value(&identifier, binding)?,
+                }
+                Ok(Item::Global(Var { ty, name: identifier, value, binding }))
+            }
+            _ => Err("only functions and variables can be defined at the top level".to_string()),
+        }
+    }
+
+    fn check_function(&mut self, fn_name: String, body: Vec<ASTNode>) -> Result<tast::Function, String>{
+        let func = self.ctx.scopes.get_fun(&fn_name).unwrap();
+
+        self.defined.push((fn_name.clone(), self.allow.clone()));
+        self.function = Some(func.clone());
+        self.ctx.scopes.push_scope();
+        for (ty, name) in &func.paras{
+            self.lint_shadowing(name);
+            self.ctx.scopes.insert_var(name.clone(), Variable::new(*ty, VarKind::Param, self.allow.clone()));
+        }
+        let body = self.check_block(body);
+        let body = self.pop_scope(body);
+        self.function = None;
+        let body = body?;
+
+        if func.ret_type != Type::VOID && !always_returns(&body){
+            return Err(format!("not all paths in {fn_name} return a value"));
+        }
+        Ok(tast::Function {
+            name: fn_name,
+            params: func.paras,
+            ret_type: func.ret_type,
+            body,
+        })
+    }
+
+    fn check_block(&mut self, body: Vec<ASTNode>) -> Result<Vec<Stmt>, String>{
+        self.ctx.scopes.push_scope();
+        let mut stmts = Vec::new();
+        let res = body.into_iter().try_for_each(|stat| self.check_statement(stat, &mut stmts));
+        self.pop_scope(res)?;
+
+        // statements after one that always returns are still checked, but never run
+        if let Some(end) = stmts.iter().position(|stmt| always_returns(std::slice::from_ref(stmt))){
+            if end + 1 < stmts.len(){
+                let fn_name = self.function.as_ref().map(|func| func.fn_name.as_str()).unwrap_or_default();
+                self.ctx.lint(Lint::UnreachableCode, &self.allow, format!("unreachable code in {fn_name}"));
+                stmts.truncate(end + 1);
+            }
+        }
+        Ok(stmts)
+    }
+
+    fn check_statement(&mut self, node: ASTNode, out: &mut Vec<Stmt>) -> Result<(), String>{
+        let stmt = match node {
+            ASTNode::Attribute { name, args, item } => {
+                let lints = attribute_lints(&name, &args, &item)?;
+                return self.with_allowed(lints, |checker| checker.check_statement(*item, out));
+            }
+            ASTNode::VariableDefinition { var_type, identifier, var_value, binding } => {
+                let var = self.check_var(var_type, identifier, var_value, binding)?;
+                // reads of a `const` are replaced by its value, so it needs no slot
+                if binding == Binding::Const{
+                    return Ok(());
+                }
+                Stmt::Var(var)
+            }
+            ASTNode::Assignment { identifier, var_value } => {
+                let Some(var) = self.ctx.scopes.get_var(&identifier) else {
+                    return Err(format!("No variable {identifier}"));
+                };
+                match (var.binding, var.kind) {
+                    (Binding::Mutable, _) => {},
+                    (Binding::Const, _) => return Err(format!("cannot assign to const '{identifier}'")),
+                    (_, VarKind::Param) => return Err(format!("cannot assign to parameter '{identifier}'")),
+                    _ => return Err(format!("cannot assign to immutable '{identifier}', declare it with `mut`")),
+                }
+                let var_type = var.ty;
+                let value = var_value.ok_or_else(|| format!("missing value for {identifier}"))?;
+                let value = self.check_expr(*value)?;
+                self.unassigned.remove(&identifier);
+                if value.ty != var_type{
+                    return Err(format!("cannot assign {} type to {} type", self.name(value.ty), self.name(var_type)));
+                }
+                Stmt::Assign { name: identifier, value }
+            }
+            ASTNode::Print { args, newline } => {
+                // each argument becomes a call to the runtime function for its type
+                for arg in args{
+                    let arg = self.check_expr(arg)?;
+                    let Some(name) = print_function(arg.ty) else {
+                        return Err(format!("cannot print {} type", self.name(arg.ty)));
+                    };
+                    let name = name.to_string();
+                    out.push(Stmt::Expr(Expr::new(ExprKind::Call { name, args: vec![arg] }, Type::VOID)));
+                }
+                if newline{
+                    let name = "print_newline".to_string();
+                    out.push(Stmt::Expr(Expr::new(ExprKind::Call { name, args: Vec::new() }, Type::VOID)));
+                }
+                return Ok(());
+            }
+            ASTNode::IfElse { condition, if_body, elif_body, el_condition, else_body } => {
+                let cond = self.check_condition(*condition, "if")?;
+                // a local is assigned after the `if` when every branch that
+                // does not return assigns it
+                let before = self.unassigned.clone();
+                let mut after = HashSet::new();
+                let then_body = self.check_branch(if_body, &mut after)?;
+                let mut elifs = Vec::new();
+                for (cond, body) in el_condition.into_iter().zip(elif_body){
+                    self.unassigned = before.clone();
+                    let cond = self.check_condition(cond, "elif")?;
+                    elifs.push((cond, self.check_branch(body, &mut after)?));
+                }
+                self.unassigned = before.clone();
+                let else_body = match else_body {
+                    Some(body) => Some(self.check_branch(body, &mut after)?),
+                    None => {
+                        after.extend(before);
+                        None
+                    }
+                };
+                self.unassigned = after;
+                Stmt::If { cond, then_body, elifs, else_body }
+            }
+            ASTNode::While { condition, body } => {
+                let cond = self.check_condition(*condition, "while")?;
+                // the body may not run at all
+                let before = self.unassigned.clone();
+                let body = self.check_block(body)?;
+                self.unassigned = before;
+                Stmt::While { cond, body }
+            }
+            ASTNode::Return(expr) => Stmt::Return(self.check_return(expr.map(|expr| *expr))?),
+            ASTNode::FunctionDefinition { fn_name, .. } =>
+                return Err(format!("function '{fn_name}' must be defined at the top level")),
+            expr => Stmt::Expr(self.check_expr(expr)?),
+        };
+        out.push(stmt);
+        Ok(())
+    }
+
+    fn check_var(&mut self, var_type: String, identifier: String, var_value: Option<Box<ASTNode>>, binding: Binding) -> Result<Var, String>{
+        if self.ctx.scopes.has_local(&identifier){
+            return Err(format!("Variable '{}' is already defined", identifier));
+        }
+        let ty = self.ctx.types.resolve(&var_type)?;
+        let mut value = self.check_init(ty, var_value)?;
+        if value.is_none(){
+            needs_value(&identifier, binding)?;
+        }
+        if binding == Binding::Const{
+            let init = value.as_ref().expect("a const has a value");
+            let folded = consteval::eval(init, &mut |var| self.const_value(var))
+                .map_err(|err| format!("cannot compute '{identifier}' at compile time: {err}"))?;
+            value = Some(folded);
+        }
+        if value.is_none(){
+            self.unassigned.insert(identifier.clone());
+            if self.ctx.zero_init{
+                value = Some(Expr::new(ExprKind::Zero, ty));
+            }
+        }
+        self.lint_shadowing(&identifier);
+        let mut var = Variable::new(ty, VarKind::Local, self.allow.clone());
+        var.binding = binding;
+        if binding == Binding::Const{
+            var.value = value.clone();
+        }
+        self.ctx.scopes.insert_var(identifier.clone(), var);
+        Ok(Var { ty, name: identifier, value, binding })
+    }
+
+    /// The value of the variable `var` reads, which must be a `const`.
+    fn const_value(&mut self, var: &Expr) -> Result<Expr, String>{
+        let (name, entry) = match &var.kind {
+            ExprKind::Global(name) => (name, self.ctx.scopes.global_scope().get_variable(name)),
+            ExprKind::Variable(name) => (name, self.ctx.scopes.get_var(name)),
+            _ => unreachable!("only variables are looked up"),
+        };
+        let entry = entry.expect("a checked name is defined");
+        if entry.binding != Binding::Const{
+            return Err(format!("'{name}' is not a constant"));
+        }
+        if let Some(value) = &entry.value{
+            return Ok(value.clone());
+        }
+        // a global const read before its own turn came
+        let name = name.clone();
+        self.eval_global(&name)
+    }
+
+    /// Computes the initial value of the global `name`, and keeps it when the
+    /// global is a `const`.
+    fn eval_global(&mut self, name: &str) -> Result<Expr, String>{
+        if let Some(value) = &self.ctx.scopes.global_scope().get_variable(name).unwrap().value{
+            return Ok(value.clone());
+        }
+        if self.evaluating.iter().any(|global| global == name){
+            return Err(format!("the value of '{name}' depends on itself"));
+        }
+        let init = self.global_inits[name].clone();
+        self.evaluating.push(name.to_string());
+        let value = consteval::eval(&init, &mut |var| self.const_value(var));
+        self.evaluating.pop();
+        let value = value.map_err(|err| format!("cannot compute '{name}' at compile time: {err}"))?;
+
+        let var = self.ctx.scopes.global_scope_mut().get_variable_mut(name).unwrap();
+        if var.binding == Binding::Const{
+            var.value = Some(value.clone());
+        }
+        Ok(value)
+    }
+
+    /// Checks the initial value of a variable of type `ty`.
+    fn check_init(&mut self, ty: Type, var_value: Option<Box<ASTNode>>) -> Result<Option<Expr>, String>{
+        let Some(value) = var_value else {
+            return Ok(None);
+        };
+        let value = self.check_expr(*value)?;
+        if value.ty != ty{
+            return Err(format!("cannot assign {} type to {} type", self.name(value.ty), self.name(ty)));
+        }
+        Ok(Some(value))
+    }
+
+    fn check_call(&mut self, fn_name: String, argument: Vec<ASTNode>) -> Result<Expr, String>{
+        let Some(info) = self.ctx.scopes.get_fun(&fn_name) else {
+            return Err(format!("No function '{}'", fn_name));
+        };
+        if let Some(caller) = &self.function{
+            self.calls.entry(caller.fn_name.clone()).or_default().push(fn_name.clone());
+        }
+        if argument.len() != info.paras.len(){
+            let len = info.paras.len();
+            let alen = argument.len();
+            return Err(format!("function {fn_name} need {len} parameters, but find {alen}."));
+        }
+        let mut args = Vec::new();
+        for (i, (arg, (para_type, para_name))) in argument.into_iter().zip(&info.paras).enumerate(){
+            let arg = self.check_expr(arg)?;
+            if arg.ty != *para_type{
+                return Err(format!(
+                    "argument {} of {fn_name} ({para_name}) must be {}, found {}",
+                    i + 1, self.name(*para_type), self.name(arg.ty)
+                ));
+            }
+            args.push(arg);
+        }
+        Ok(Expr::new(ExprKind::Call { name: fn_name, args }, info.ret_type))
+    }
+
+    /// Checks one branch of an `if`, adding the locals it may leave unassigned to `after`.
+    fn check_branch(&mut self, body: Vec<ASTNode>, after: &mut HashSet<String>) -> Result<Vec<Stmt>, String>{
+        let body = self.check_block(body)?;
+        if !always_returns(&body){
+            after.extend(self.unassigned.drain());
+        }
+        Ok(body)
+    }
+
+    /// Rejects a read of a local that may not be assigned yet, or warns
+    /// about it when such locals start as zero.
+    fn check_assigned(&mut self, name: &str) -> Result<(), String>{
+        if !self.unassigned.contains(name){
+            return Ok(());
+        }
+        let fn_name = self.function.as_ref().map(|func| func.fn_name.as_str()).unwrap_or_default();
+        let message = format!("variable '{name}' in {fn_name} may be read before it is assigned");
+        if !self.ctx.zero_init{
+            return Err(message);
+        }
+        let message = format!("{message}, it starts as zero");
+        if !self.ctx.warnings.contains(&message){
+            self.ctx.warnings.push(message);
+        }
+        self.unassigned.remove(name);
+        Ok(())
+    }
+
+    fn check_condition(&mut self, cond: ASTNode, keyword: &str) -> Result<Expr, String>{
+        let cond = self.check_expr(cond)?;
+        if cond.ty != Type::BOOL{
+            return Err(format!("{keyword} condition must be bool, found {}", self.name(cond.ty)));
+        }
+        Ok(cond)
+    }
+
+    fn check_return(&mut self, value: Option<ASTNode>) -> Result<Option<Expr>, String>{
+        let func = self.function.clone().expect("ret is only checked inside a function");
+        let Some(value) = value else {
+            if func.ret_type != Type::VOID{
+                return Err(format!("{} returns {}, but ret gives no value", func.fn_name, self.name(func.ret_type)));
+            }
+            return Ok(None);
+        };
+        let value = self.check_expr(value)?;
+        if func.ret_type == Type::VOID{
+            return Err(format!("{} does not return a value, but ret gives {}", func.fn_name, self.name(value.ty)));
+        }
+        if value.ty != func.ret_type{
+            return Err(format!(
+                "{} returns {}, but ret gives {}",
+                func.fn_name, self.name(func.ret_type), self.name(value.ty)
+            ));
+        }
+        Ok(Some(value))
+    }
+
+    /// The type of `left op right`, both operands must have the same type and
+    /// the operator must be defined for it.
+    fn binary_type(&self, left: Type, op: &str, right: Type) -> Result<Type, String>{
+        if left != right{
+            return Err(format!("mismatched types: cannot apply `{op}` to {} and {}", self.name(left), self.name(right)));
+        }
+        let allowed: &[Type] = match op {
+            "+" => &[Type::I32, Type::F64, Type::STR],
+            "-" | "*" | "/" | "%" => &[Type::I32, Type::F64],
+            "<" | "<=" | ">" | ">=" => &[Type::I32, Type::F64, Type::CHAR, Type::STR],
+            "==" | "!=" => &[Type::I32, Type::F64, Type::CHAR, Type::STR, Type::BOOL],
+            _ => &[],
+        };
+        if !allowed.contains(&left){
+            return Err(format!("cannot apply `{op}` to {}", self.name(left)));
+        }
+        Ok(if is_comparison(op) { Type::BOOL } else { left })
+    }
+
+    fn check_index(&mut self, index: ASTNode) -> Result<Expr, String>{
+        let index = self.check_expr(index)?;
+        if index.ty != Type::I32{
+            return Err(format!("index must be i32, found {}", self.name(index.ty)));
+        }
+        Ok(index)
+    }
+
+    fn check_expr(&mut self, node: ASTNode) -> Result<Expr, String>{
+        let expr = match node {
+            ASTNode::Number(n) => {
+                let n = n.parse::<i32>().map_err(|_| format!("{n} does not fit in i32"))?;
+                Expr::new(ExprKind::Int(n), Type::I32)
+            }
+            ASTNode::Float(f) => {
+                let f = f.parse::<f64>().map_err(|_| format!("'{f}' is not a valid f64 literal"))?;
+                Expr::new(ExprKind::Float(f), Type::F64)
+            }
+            ASTNode::Bool(b) => Expr::new(ExprKind::Bool(b), Type::BOOL),
+            ASTNode::Char(c) => Expr::new(ExprKind::Char(c), Type::CHAR),
+            ASTNode::String(s) => Expr::new(ExprKind::Str(s), Type::STR),
+            ASTNode::Identifier(name) => {
+                let Some(ty) = self.ctx.scopes.read_var(&name) else {
+                    return Err(format!("No such variable {}", name));
+                };
+                self.check_assigned(&name)?;
+                if let Some(value) = self.ctx.scopes.get_var(&name).and_then(|var| var.value.clone()){
+                    return Ok(value);
+                }
+                Expr::new(ExprKind::Variable(name), ty)
+            }
+            ASTNode::GlobalIdentifier(name) => {
+                let Some(ty) = self.ctx.scopes.global_scope_mut().read_variable(&name) else {
+                    return Err(format!("No such variable {}", name));
+                };
+                if let Some(value) = self.ctx.scopes.global_scope().get_variable(&name).and_then(|var| var.value.clone()){
+                    return Ok(value);
+                }
+                Expr::new(ExprKind::Global(name), ty)
+            }
+            ASTNode::FunctionCall { fn_name, argument } => self.check_call(fn_name, argument)?,
+            ASTNode::InfixExpression { left_expr, op, right_expr } => {
+                let left = self.check_expr(*left_expr)?;
+                let right = self.check_expr(*right_expr)?;
+                let ty = self.binary_type(left.ty, &op, right.ty)?;
+                Expr::new(ExprKind::Binary { left: Box::new(left), op, right: Box::new(right) }, ty)
+            }
+            ASTNode::Slice { expr, start, end } => {
+                let string = self.check_expr(*expr)?;
+                if string.ty != Type::STR{
+                    return Err(format!("cannot slice {} type", self.name(string.ty)));
+                }
+                let start = match start {
+                    Some(start) => Some(Box::new(self.check_index(*start)?)),
+                    None => None,
+                };
+                let end = match end {
+                    Some(end) => Some(Box::new(self.check_index(*end)?)),
+                    None => None,
+                };
+                Expr::new(ExprKind::Slice { string: Box::new(string), start, end }, Type::STR)
+            }
+            ASTNode::Index { expr, index } => {
+                let array = self.check_expr(*expr)?;
+                let Some(elem_type) = self.ctx.types.elem(array.ty) else {
+                    return Err(format!("cannot index {} type", self.name(array.ty)));
+                };
+                let index = self.check_index(*index)?;
+                Expr::new(ExprKind::Index { array: Box::new(array), index: Box::new(index) }, elem_type)
+            }
+            ASTNode::MethodCall { receiver, method, .. } => {
+                let receiver = self.check_expr(*receiver)?;
+                let has_len = receiver.ty == Type::STR || self.ctx.types.elem(receiver.ty).is_some();
+                if !has_len || method != "len"{
+                    return Err(format!("{} type has no method '{}'", self.name(receiver.ty), method));
+                }
+                Expr::new(ExprKind::Len(Box::new(receiver)), Type::I32)
+            }
+            ASTNode::Interpolation(parts) => {
+                let mut checked = Vec::new();
+                for part in parts{
+                    let part = self.check_expr(part)?;
+                    if print_function(part.ty).is_none(){
+                        return Err(format!("cannot format {} type in a string", self.name(part.ty)));
+                    }
+                    checked.push(part);
+                }
+                Expr::new(ExprKind::Interpolation(checked), Type::STR)
+            }
+            ASTNode::Expression(expr) => self.check_expr(*expr)?,
+            node => return Err(format!("expected an expression, found {:?}", node)),
+        };
+        Ok(expr)
+    }
+}
+
+/// Only a `mut` variable may be defined without a value and assigned later.
+fn needs_value(name: &str, binding: Binding) -> Result<(), String>{
+    match binding {
+        Binding::Mutable => Ok(()),
+        Binding::Const => Err(format!("const '{name}' needs a value")),
+        Binding::Immutable => Err(format!("immutable '{name}' needs a value, declare it with `mut` to assign it later")),
+    }
+}
+
+/// Whether `node` defines a `sub`, attributes and all.
+fn is_function(node: &ASTNode) -> bool{
+    match node {
+        ASTNode::Attribute { item, .. } => is_function(item),
+        node => matches!(node, ASTNode::FunctionDefinition { .. }),
+    }
+}
+
+/// The lints an `#[allow(...)]` on `item` turns off.
+fn attribute_lints(name: &str, args: &[String], item: &ASTNode) -> Result<Vec<Lint>, String>{
+    if name != "allow"{
+        return Err(format!("unknown attribute '{name}'"));
+    }
+    if !matches!(item, ASTNode::FunctionDefinition { .. } | ASTNode::VariableDefinition { .. }){
+        return Err("#[allow] can only be put on a sub or a variable".to_string());
+    }
+    args.iter().map(|arg| Lint::from_name(arg).ok_or_else(|| format!("unknown lint '{arg}'"))).collect()
+}
+
+/// Whether control can never reach the end of `body`: every path returns,
+/// exits the program or loops forever.
+fn always_returns(body: &[Stmt]) -> bool{
+    body.iter().any(|stmt| match stmt {
+        Stmt::Return(_) => true,
+        Stmt::If { then_body, elifs, else_body: Some(else_body), .. } =>
+            always_returns(then_body)
+                && elifs.iter().all(|(_, body)| always_returns(body))
+                && always_returns(else_body),
+        Stmt::While { cond: Expr { kind: ExprKind::Bool(true), .. }, .. } => true,
+        Stmt::Expr(Expr { kind: ExprKind::Call { name, .. }, .. }) => name == "exit",
+        _ => false,
+    })
+}
+
+pub fn is_comparison(op: &str) -> bool{
+    matches!(op, "==" | "!=" | "<" | "<=" | ">" | ">=")
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn add_has_value(){
+        let mut st = SymbolTable::new();
+        st.add_variable("a".to_string(), Variable::new(Type::I32, VarKind::Local, Vec::new()));
+        let res1 = st.has_variable("a");
+        let res2 = st.has_variable("b");
+
+        assert!(res1);
+        assert!(!res2);
+    }
+
+    #[test]
+    fn lookup(){
+        let mut st = SymbolTable::new();
+        st.add_variable("a".to_string(), Variable::new(Type::I32, VarKind::Local, Vec::new()));
+        let res1 = st.lookup_variable("a");
+        let res2 = st.lookup_variable("b");
+        assert_eq!(res1, Some(Type::I32));
+        assert_eq!(res2, None); 
+    }
+
+    #[test]
+    fn print_functions_exist(){
+        let lib = lib_functions();
+        for ty in [Type::I32, Type::BOOL, Type::STR, Type::CHAR, Type::F64]{
+            let name = print_function(ty).unwrap();
+            let func = lib.iter().find(|f| f.fn_name == name).unwrap();
+            assert_eq!(func.paras[0].0, ty);
+        }
+        assert!(print_function(Type::VOID).is_none());
+    }
+
+    /// Parses `input` and checks it with `ctx`, a parse error comes back like a sema one.
+    fn check_with(input: &str, ctx: &mut Context) -> Result<Program, String>{
+        let ast = crate::parse(crate::lex(input).unwrap()).map_err(|err| err[0].message.clone())?;
+        check_program(ast, ctx)
+    }
+
+    fn check_source(input: &str) -> Result<Program, String>{
+        check_with(input, &mut Context::new())
+    }
+
+    #[test]
+    fn slice_non_string(){
+        let input = "i32 slice_num = 5; i32 slice_bad = slice_num[0..1];";
+        assert!(check_source(input).is_err());
+    }
+
+    #[test]
+    fn index_types(){
+        let input = "sub index_test(i32[] nums) -> i32 { ret nums[1]; }";
+        let program = check_source(input).unwrap();
+        let Item::Function(func) = &program.items[0] else {
+            panic!("Expected a function");
+        };
+        let Stmt::Return(Some(expr)) = &func.body[0] else {
+            panic!("Expected Return statement in function body.");
+        };
+        assert_eq!(expr.ty, Type::I32);
+        assert!(check_source("sub index_bad(str[] args) -> str { ret args[true]; }").is_err());
+    }
+
+    #[test]
+    fn println_resolves_by_type(){
+        let input = "sub print_test(){ println(1, true, 'c', 2.5, \"s\"); }";
+        let program = check_source(input).unwrap();
+        let Item::Function(func) = &program.items[0] else {
+            panic!("Expected a function");
+        };
+        let names: Vec<&str> = func.body.iter().map(|stat| match stat {
+            Stmt::Expr(Expr { kind: ExprKind::Call { name, .. }, .. }) => name.as_str(),
+            _ => panic!("Expected a call for each printed value."),
+        }).collect();
+        assert_eq!(names, ["print_i32", "print_bool", "print_char", "print_f64", "print_str", "print_newline"]);
+    }
+
+    #[test]
+    fn type_errors(){
+        let err = |input: &str| check_source(input).unwrap_err();
+        assert_eq!(err("sub f() { str s = \"abc\" + 1; }"), "mismatched types: cannot apply `+` to str and i32");
+        assert_eq!(err("sub f() { bool b = true * false; }"), "cannot apply `*` to bool");
+        assert_eq!(err("sub f() { if 1 { } }"), "if condition must be bool, found i32");
+        assert_eq!(err("sub f() { while \"x\" { } }"), "while condition must be bool, found str");
+        assert_eq!(err("sub f() { echo(1); }"), "argument 1 of echo (string) must be str, found i32");
+        assert_eq!(err("sub f() -> i32 { ret \"x\"; }"), "f returns i32, but ret gives str");
+        assert_eq!(err("sub f() { ret 1; }"), "f does not return a value, but ret gives i32");
+        assert_eq!(err("sub f() -> str { ret; }"), "f returns str, but ret gives no value");
+        assert!(check_source("sub f(bool b) { if b { ret; } echo(\"b is false\"); }").is_ok());
+        assert_eq!(err("sub f() { mut i32 x = 1; x = 'c'; }"), "cannot assign char type to i32 type");
+        assert!(check_source("sub f(str a, str b) -> bool { ret a < b; }").is_ok());
+    }
+
+    #[test]
+    fn forward_references(){
+        let input = "sub is_even(i32 n) -> bool { if n == 0 { ret true; } ret is_odd(n - 1); }
+                     sub is_odd(i32 n) -> bool { if n == 0 { ret false; } ret is_even(n - 1); }
+                     sub show() { println(limit); }
+                     i32 limit = 10;";
+        assert!(check_source(input).is_ok());
+        assert_eq!(check_source("sub f() { } sub f() { }").unwrap_err(), "Function 'f' is already defined");
+        assert_eq!(check_source("sub f(i32 a, i32 a) { }").unwrap_err(), "Parameter 'a' is already defined");
+    }
+
+    #[test]
+    fn globals_and_functions_clash(){
+        assert_eq!(check_source("i32 f = 1; sub f() { }").unwrap_err(), "'f' is already defined as a variable");
+        assert_eq!(check_source("sub f() { } i32 f = 1;").unwrap_err(), "'f' is already defined as a function");
+        assert_eq!(check_source("i32 echo = 3; sub main() { echo(\"x\"); }").unwrap_err(), "'echo' is already defined as a function");
+    }
+
+    #[test]
+    fn return_paths(){
+        let err = |input: &str| check_source(input).unwrap_err();
+        assert_eq!(err("sub f(i32 x) -> i32 { if x < 0 { ret 0; } }"), "not all paths in f return a value");
+        assert_eq!(err("sub f(bool b) -> i32 { if b { ret 0; } elif b == false { ret 1; } }"), "not all paths in f return a value");
+        assert!(check_source("sub f(bool b) -> i32 { if b { ret 0; } else { ret 1; } }").is_ok());
+        assert!(check_source("sub f() -> i32 { while true { } }").is_ok());
+        assert!(check_source("sub f() -> i32 { exit(1); }").is_ok());
+    }
+
+    #[test]
+    fn code_after_ret(){
+        let mut ctx = Context::new();
+        let program = check_with("sub f() -> i32 { ret 1; echo(\"never\"); }", &mut ctx).unwrap();
+
+        assert_eq!(ctx.warnings, ["unreachable code in f [unreachable_code]"]);
+        let Item::Function(func) = &program.items[0] else {
+            panic!("Expected a function");
+        };
+        assert_eq!(func.body.len(), 1);
+
+        let mut lints = LintLevels::default();
+        assert_eq!(check_warnings("sub main() { exit(1); echo(\"never\"); }", lints.clone()), ["unreachable code in main [unreachable_code]"]);
+        assert_eq!(check_warnings("sub main() { while true { } echo(\"never\"); }", lints.clone()), ["unreachable code in main [unreachable_code]"]);
+        assert!(check_warnings("#[allow(unreachable_code)] sub main() { exit(1); echo(\"never\"); }", lints.clone()).is_empty());
+        lints.allow(Lint::UnreachableCode);
+        assert!(check_warnings("sub main() { exit(1); echo(\"never\"); }", lints).is_empty());
+    }
+
+    #[test]
+    fn undefined_names(){
+        assert_eq!(check_source("sub f() { x = 2; }").unwrap_err(), "No variable x");
+        assert_eq!(check_source("sub f() { g(); }").unwrap_err(), "No function 'g'");
+        assert!(check_source("sub f() { i32 y = 1; i32 y = 2; }").is_err());
+    }
+
+    #[test]
+    fn main_signature(){
+        let main = |parameters: Vec<(String, String)>, ret_type: Option<&str>| ASTNode::FunctionDefinition {
+            fn_name: "main".to_string(),
+            parameters,
+            ret_type: ret_type.map(String::from),
+            body: vec![],
+        };
+        let args = vec![("str[]".to_string(), "args".to_string())];
+
+        assert!(check_main(&[main(vec![], None)]).is_ok());
+        assert!(check_main(&[main(args.clone(), Some("i32"))]).is_ok());
+        assert!(check_main(&[main(vec![("i32".to_string(), "argc".to_string())], None)]).is_err());
+        assert!(check_main(&[main(args, Some("str"))]).is_err());
+    }
+
+    fn check_warnings(input: &str, lints: LintLevels) -> Vec<String>{
+        let mut ctx = Context { lints, ..Context::new() };
+        check_with(input, &mut ctx).unwrap();
+        ctx.warnings
+    }
+
+    #[test]
+    fn lints(){
+        let input = "i32 limit = 3;
+                     i32 spare = 0;
+                     sub helper(i32 a, i32 b) -> i32 { i32 tmp = a; ret a; }
+                     sub orphan() { }
+                     sub main() { i32 limit = 1; println(limit, @limit, helper(1, 2)); }";
+        assert_eq!(check_warnings(input, LintLevels::default()), [
+            "unused variable 'tmp' in helper [unused_variables]",
+            "unused parameter 'b' of helper [unused_parameters]",
+            "local 'limit' in main shadows a global, use @limit for the global [shadowing]",
+            "unused global 'spare' [unused_variables]",
+            "function 'orphan' is never called from main [dead_code]",
+        ]);
+
+        let mut levels = LintLevels::default();
+        for lint in [Lint::UnusedVariables, Lint::UnusedParameters, Lint::Shadowing]{
+            levels.allow(lint);
+        }
+        assert_eq!(check_warnings(input, levels), ["function 'orphan' is never called from main [dead_code]"]);
+
+        // without a main every function may be called from outside
+        assert!(check_warnings("sub orphan() { }", LintLevels::default()).is_empty());
+    }
+
+    #[test]
+    fn definite_assignment(){
+        let err = |input: &str| check_source(input).unwrap_err();
+        assert_eq!(err("sub f() -> i32 { mut i32 x; ret x; }"), "variable 'x' in f may be read before it is assigned");
+        assert_eq!(err("sub f(bool b) -> i32 { mut i32 x; if b { x = 1; } ret x; }"), "variable 'x' in f may be read before it is assigned");
+        assert_eq!(err("sub f(bool b) -> i32 { mut i32 x; while b { x = 1; } ret x; }"), "variable 'x' in f may be read before it is assigned");
+        assert_eq!(err("sub f() { mut i32 x; while true { println(x); x = 1; } }"), "variable 'x' in f may be read before it is assigned");
+        assert!(check_source("sub f(bool b) -> i32 { mut i32 x; if b { x = 1; } elif b == false { x = 2; } else { x = 3; } ret x; }").is_ok());
+        assert!(check_source("sub f(bool b) -> i32 { mut i32 x; if b { x = 1; } else { ret 0; } ret x; }").is_ok());
+        assert!(check_source("sub f() -> i32 { mut i32 x; x = 1; while true { ret x; } }").is_ok());
+
+        let mut ctx = Context { zero_init: true, ..Context::new() };
+        let program = check_with("sub f() -> i32 { mut i32 x; if x == 0 { ret x; } ret x; }", &mut ctx).unwrap();
+        assert_eq!(ctx.warnings, ["variable 'x' in f may be read before it is assigned, it starts as zero"]);
+        let Item::Function(func) = &program.items[0] else {
+            panic!("Expected a function");
+        };
+        let Stmt::Var(Var { value: Some(value), .. }) = &func.body[0] else {
+            panic!("Expected a zeroed variable");
+        };
+        assert_eq!(value.kind, ExprKind::Zero);
+    }
+
+    #[test]
+    fn constants(){
+        let program = check_source("i32 area = W * H; const i32 H = W + 1; const i32 W = 2; const str S = \"a\" + \"b\";").unwrap();
+        let values: Vec<ExprKind> = program.items.iter().map(|item| match item {
+            Item::Global(Var { value: Some(value), .. }) => value.kind.clone(),
+            _ => panic!("Expected a global with a value"),
+        }).collect();
+        assert_eq!(values, [ExprKind::Int(6), ExprKind::Int(3), ExprKind::Int(2), ExprKind::Str("ab".to_string())]);
+
+        let err = |input: &str| check_source(input).unwrap_err();
+        assert_eq!(err("i32 a = b; i32 b = 1;"), "cannot compute 'a' at compile time: 'b' is not a constant");
+        assert_eq!(err("str s = itos(1);"), "cannot compute 's' at compile time: calling itos is not constant");
+        assert_eq!(
+            err("const i32 a = b; const i32 b = a;"),
+            "cannot compute 'a' at compile time: cannot compute 'b' at compile time: the value of 'a' depends on itself"
+        );
+        assert_eq!(err("const i32 k;"), "const 'k' needs a value");
+        assert_eq!(err("const i32 k = 1; sub f() { k = 2; }"), "cannot assign to const 'k'");
+        assert_eq!(err("sub f(i32 n) { const i32 m = n; }"), "cannot compute 'm' at compile time: 'n' is not a constant");
+        assert!(check_source("const i32 N = 4; sub f() -> i32 { const i32 M = N * 2; ret M; }").is_ok());
+    }
+
+    #[test]
+    fn bindings(){
+        let err = |input: &str| check_source(input).unwrap_err();
+        assert_eq!(err("sub f() { i32 x = 1; x = 2; }"), "cannot assign to immutable 'x', declare it with `mut`");
+        assert_eq!(err("i32 g = 1; sub f() { g = 2; }"), "cannot assign to immutable 'g', declare it with `mut`");
+        assert_eq!(err("sub f(i32 n) { n = 2; }"), "cannot assign to parameter 'n'");
+        assert_eq!(err("sub f() { i32 x; }"), "immutable 'x' needs a value, declare it with `mut` to assign it later");
+        assert!(check_source("mut i32 g; sub f() { mut i32 x = 1; x = x + 1; g = x; }").is_ok());
+
+        // reads of a const become its value and a local const leaves no statement behind
+        let program = check_source("const i32 N = 3; sub f() -> i32 { const i32 M = N + 1; ret @N * M; }").unwrap();
+        let Item::Function(func) = &program.items[1] else {
+            panic!("Expected a function");
+        };
+        let [Stmt::Return(Some(Expr { kind: ExprKind::Binary { left, right, .. }, .. }))] = func.body.as_slice() else {
+            panic!("Expected only the return");
+        };
+        assert_eq!((&left.kind, &right.kind), (&ExprKind::Int(3), &ExprKind::Int(4)));
+    }
+
+    #[test]
+    fn allow_attributes(){
+        let input = "#[allow(dead_code, unused_parameters)] sub orphan(i32 a) { i32 b = 1; }
+                     sub main() { #[allow(unused_variables)] i32 quiet = 1; }";
+        assert_eq!(check_warnings(input, LintLevels::default()), ["unused variable 'b' in orphan [unused_variables]"]);
+
+        let err = |input: &str| check_source(input).unwrap_err();
+        assert_eq!(err("#[allow(unused)] i32 x = 1;"), "unknown lint 'unused'");
+        assert_eq!(err("#[inline] sub f() { }"), "unknown attribute 'inline'");
+        assert_eq!(err("sub f() { #[allow(dead_code)] echo(\"x\"); }"), "#[allow] can only be put on a sub or a variable");
+        assert!(check_main(&[ASTNode::Attribute {
+            name: "allow".to_string(),
+            args: Vec::new(),
+            item: Box::new(ASTNode::FunctionDefinition {
+                fn_name: "main".to_string(),
+                parameters: vec![("i32".to_string(), "argc".to_string())],
+                ret_type: None,
+                body: vec![],
+            }),
+        }]).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use std::env;
+
+/// Triples the runtime library supports, the Windows ones use `lib_for_windows.ll`
+/// and the rest the libc based `lib_for_linux.ll`.
+pub const TARGETS: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "aarch64-unknown-linux-gnu",
+    "x86_64-pc-windows-msvc",
+    "aarch64-pc-windows-msvc",
+    "x86_64-apple-darwin",
+    "aarch64-apple-darwin",
+    "x86_64-unknown-freebsd",
+];
+
+pub fn host_triple() -> String{
+    let arch = match env::consts::ARCH {
+        "aarch64" => "aarch64",
+        _ => "x86_64",
+    };
+    let vendor_os = match env::consts::OS {
+        "windows" => "pc-windows-msvc",
+        "macos" => "apple-darwin",
+        "freebsd" => "unknown-freebsd",
+        _ => "unknown-linux-gnu",
+    };
+    format!("{arch}-{vendor_os}")
+}
+
+pub fn check_target(triple: &str) -> Result<(), String>{
+    if TARGETS.contains(&triple){
+        Ok(())
+    }else{
+        Err(format!("unsupported target `{}`, see `ika --print-targets`", triple))
+    }
+}
+
+pub fn is_windows(triple: &str) -> bool{
+    triple.contains("windows")
+}
+
+/// The executable name used when `-o` is not given.
+pub fn default_exe_name(triple: &str) -> &'static str{
+    if is_windows(triple){
+        "out.exe"
+    }else{
+        "a.out"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_is_supported(){
+        assert!(check_target(&host_triple()).is_ok());
+        assert!(check_target("riscv64-unknown-linux-gnu").is_err());
+        assert_eq!(default_exe_name("aarch64-pc-windows-msvc"), "out.exe");
+    }
+
+    #[test]
+    fn unknown_target(){
+        assert_eq!(check_target("x86_64-unknown-plan9"), Err("unsupported target `x86_64-unknown-plan9`, see `ika --print-targets`".to_string()));
+    }
+}
@@ -0,0 +1,117 @@
+//! The typed AST. `sema::check_program` builds it from the parser's `ASTNode`
+//! once every name is resolved and every expression has a type, and `Codegen`
+//! lowers it to LLVM IR.
+
+use serde::Serialize;
+use crate::parser::Binding;
+use crate::types::{Type, Types};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Program {
+    pub items: Vec<Item>,
+    /// Every type the items refer to
+    pub types: Types,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Item {
+    Function(Function),
+    Global(Var),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Function {
+    pub name: String,
+    /// `(type, name)` pairs
+    pub params: Vec<(Type, String)>,
+    /// `Type::VOID` when the function returns nothing
+    pub ret_type: Type,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Var {
+    pub ty: Type,
+    pub name: String,
+    /// Always a literal for globals and `const`s
+    pub value: Option<Expr>,
+    pub binding: Binding,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Stmt {
+    Var(Var),
+    Assign {
+        name: String,
+        value: Expr,
+    },
+    Expr(Expr),
+    If {
+        cond: Expr,
+        then_body: Vec<Stmt>,
+        elifs: Vec<(Expr, Vec<Stmt>)>,
+        else_body: Option<Vec<Stmt>>,
+    },
+    While {
+        cond: Expr,
+        body: Vec<Stmt>,
+    },
+    /// `None` for a bare `ret;` in a void function
+    Return(Option<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub ty: Type,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum ExprKind {
+    Int(i32),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    Str(String),
+    /// The zero value of the expression's type
+    Zero,
+    Variable(String),
+    /// `@name`, the global even where a local shadows it
+    Global(String),
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Binary {
+        left: Box<Expr>,
+        op: String,
+        right: Box<Expr>,
+    },
+    /// `array[index]`, bounds checked at run time
+    Index {
+        array: Box<Expr>,
+        index: Box<Expr>,
+    },
+    Slice {
+        string: Box<Expr>,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+    },
+    /// `.len()` of a `str` or an array
+    Len(Box<Expr>),
+    /// The literal pieces and the values formatted between them
+    Interpolation(Vec<Expr>),
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, ty: Type) -> Self {
+        Self { kind, ty }
+    }
+
+    /// `l op r`, typed the way sema types it, for tests that build trees by hand.
+    #[cfg(test)]
+    pub fn infix(l: Expr, op: &str, r: Expr) -> Self {
+        let ty = if crate::sema::is_comparison(op) { Type::BOOL } else { l.ty };
+        Self::new(ExprKind::Binary { left: Box::new(l), op: op.to_string(), right: Box::new(r) }, ty)
+    }
+}
@@ -0,0 +1,110 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The generated IR uses opaque pointers, which LLVM reads by default since 15.
+pub const MIN_LLVM_VERSION: u32 = 15;
+
+/// Versioned names like `clang-18` are probed from here down to `MIN_LLVM_VERSION`.
+const NEWEST_PROBED_VERSION: u32 = 30;
+
+#[derive(Debug, Clone)]
+pub struct Tool {
+    pub path: PathBuf,
+    pub version: (u32, u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Toolchain {
+    pub clang: Tool,
+    pub llvm_link: Tool,
+}
+
+impl Toolchain {
+    pub fn discover() -> Result<Self, String>{
+        Ok(Self {
+            clang: find_tool("clang", "IKA_CLANG")?,
+            llvm_link: find_tool("llvm-link", "IKA_LLVM_LINK")?,
+        })
+    }
+}
+
+/// Looks for `name` in `$env_var`, then on PATH as `name` and `name-<version>`,
+/// skipping anything older than `MIN_LLVM_VERSION`.
+pub fn find_tool(name: &str, env_var: &str) -> Result<Tool, String>{
+    if let Some(path) = env::var_os(env_var){
+        let path = PathBuf::from(path);
+        return match probe(&path)? {
+            Some(tool) => Ok(tool),
+            None => Err(format!("${} is set to {}, which is not a working {}", env_var, path.display(), name)),
+        };
+    }
+
+    let mut too_old = None;
+    let names = std::iter::once(name.to_string())
+        .chain((MIN_LLVM_VERSION..=NEWEST_PROBED_VERSION).rev().map(|v| format!("{name}-{v}")));
+    for candidate in names{
+        let Some(path) = search_path(&candidate) else { continue };
+        match probe(&path) {
+            Ok(Some(tool)) => return Ok(tool),
+            Ok(None) => {},
+            Err(err) => {
+                too_old.get_or_insert(err);
+            },
+        }
+    }
+
+    Err(match too_old {
+        Some(err) => format!("{err}, install a newer one or set ${env_var} to its path"),
+        None => format!(
+            "cannot find {name}, install LLVM {MIN_LLVM_VERSION} or newer (https://github.com/llvm/llvm-project/releases) \
+             or set ${env_var} to its path"
+        ),
+    })
+}
+
+/// `Ok(None)` when `path` does not run or does not report a version.
+fn probe(path: &Path) -> Result<Option<Tool>, String>{
+    let Ok(output) = Command::new(path).arg("--version").output() else {
+        return Ok(None);
+    };
+    let Some(version) = parse_version(&String::from_utf8_lossy(&output.stdout)) else {
+        return Ok(None);
+    };
+    if version.0 < MIN_LLVM_VERSION{
+        return Err(format!(
+            "{} is LLVM {}.{}, but ika needs LLVM {} or newer",
+            path.display(), version.0, version.1, MIN_LLVM_VERSION
+        ));
+    }
+    Ok(Some(Tool { path: path.to_path_buf(), version }))
+}
+
+fn search_path(name: &str) -> Option<PathBuf>{
+    let file = format!("{name}{}", env::consts::EXE_SUFFIX);
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&file))
+        .find(|path| path.is_file())
+}
+
+/// Reads the `major.minor` after "version" in `--version` output, e.g.
+/// "clang version 18.1.3" or "LLVM version 17.0.6".
+fn parse_version(output: &str) -> Option<(u32, u32)>{
+    let rest = &output[output.find("version ")? + "version ".len()..];
+    let mut numbers = rest.split(|c: char| !c.is_ascii_digit());
+    let major = numbers.next()?.parse().ok()?;
+    let minor = numbers.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_output(){
+        assert_eq!(parse_version("Ubuntu clang version 18.1.3 (1ubuntu1)\nTarget: x86_64"), Some((18, 1)));
+        assert_eq!(parse_version("LLVM (http://llvm.org/):\n  LLVM version 17.0.6\n"), Some((17, 0)));
+        assert_eq!(parse_version("no version here"), None);
+    }
+}
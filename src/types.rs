@@ -0,0 +1,175 @@
+//! Types are interned: a `Type` is a handle into `Types`, so it is cheap to
+//! copy and two types are equal exactly when their handles are. The
+//! primitives have fixed handles and need no table to be named.
+
+use std::collections::HashMap;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct Type(u32);
+
+impl Type {
+    pub const VOID: Type = Type(0);
+    pub const I32: Type = Type(1);
+    pub const BOOL: Type = Type(2);
+    pub const CHAR: Type = Type(3);
+    pub const F64: Type = Type(4);
+    pub const STR: Type = Type(5);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum TypeKind {
+    Void,
+    I32,
+    Bool,
+    Char,
+    F64,
+    Str,
+    Pointer(Type),
+    /// `T[]`, a pointer and a length like `str`
+    Array(Type),
+    Struct {
+        name: String,
+        fields: Vec<Type>,
+    },
+    Function {
+        params: Vec<Type>,
+        ret: Type,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Types {
+    kinds: Vec<TypeKind>,
+    #[serde(skip)]
+    ids: HashMap<TypeKind, Type>,
+}
+
+impl Default for Types {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Types {
+    pub fn new() -> Self {
+        let mut types = Self { kinds: Vec::new(), ids: HashMap::new() };
+        // interned in the order of the `Type` constants
+        for kind in [TypeKind::Void, TypeKind::I32, TypeKind::Bool, TypeKind::Char, TypeKind::F64, TypeKind::Str]{
+            types.intern(kind);
+        }
+        types
+    }
+
+    pub fn intern(&mut self, kind: TypeKind) -> Type{
+        if let Some(ty) = self.ids.get(&kind){
+            return *ty;
+        }
+        let ty = Type(self.kinds.len() as u32);
+        self.kinds.push(kind.clone());
+        self.ids.insert(kind, ty);
+        ty
+    }
+
+    pub fn kind(&self, ty: Type) -> &TypeKind{
+        &self.kinds[ty.0 as usize]
+    }
+
+    pub fn array(&mut self, elem: Type) -> Type{
+        self.intern(TypeKind::Array(elem))
+    }
+
+    pub fn function(&mut self, params: Vec<Type>, ret: Type) -> Type{
+        self.intern(TypeKind::Function { params, ret })
+    }
+
+    /// The element type when `ty` is an array.
+    pub fn elem(&self, ty: Type) -> Option<Type>{
+        match self.kind(ty) {
+            TypeKind::Array(elem) => Some(*elem),
+            _ => None,
+        }
+    }
+
+    /// Resolves a type as the parser wrote it, a primitive or `T[]`.
+    pub fn resolve(&mut self, name: &str) -> Result<Type, String>{
+        if let Some(elem) = name.strip_suffix("[]"){
+            let elem = self.resolve(elem)?;
+            return Ok(self.array(elem));
+        }
+        match name {
+            "void" => Ok(Type::VOID),
+            "i32" => Ok(Type::I32),
+            "bool" => Ok(Type::BOOL),
+            "char" => Ok(Type::CHAR),
+            "f64" => Ok(Type::F64),
+            "str" => Ok(Type::STR),
+            _ => Err(format!("{name} is not a valid type.")),
+        }
+    }
+
+    /// `ty` as it is written in ika source.
+    pub fn name(&self, ty: Type) -> String{
+        match self.kind(ty) {
+            TypeKind::Void => "void".to_string(),
+            TypeKind::I32 => "i32".to_string(),
+            TypeKind::Bool => "bool".to_string(),
+            TypeKind::Char => "char".to_string(),
+            TypeKind::F64 => "f64".to_string(),
+            TypeKind::Str => "str".to_string(),
+            TypeKind::Pointer(to) => format!("{}*", self.name(*to)),
+            TypeKind::Array(elem) => format!("{}[]", self.name(*elem)),
+            TypeKind::Struct { name, .. } => name.clone(),
+            TypeKind::Function { params, ret } => {
+                let params: Vec<String> = params.iter().map(|p| self.name(*p)).collect();
+                format!("sub({}) -> {}", params.join(", "), self.name(*ret))
+            }
+        }
+    }
+
+    /// The LLVM type values of `ty` are lowered to.
+    pub fn llvm_type(&self, ty: Type) -> String{
+        match self.kind(ty) {
+            TypeKind::Void => "void".to_string(),
+            TypeKind::I32 => "i32".to_string(),
+            TypeKind::Bool => "i1".to_string(),
+            TypeKind::Char => "i8".to_string(),
+            TypeKind::F64 => "double".to_string(),
+            TypeKind::Str => "%str".to_string(),
+            TypeKind::Array(_) => "%arr".to_string(),
+            TypeKind::Struct { name, .. } => format!("%{name}"),
+            TypeKind::Pointer(_) | TypeKind::Function { .. } => "ptr".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning(){
+        let mut types = Types::new();
+        let a = types.resolve("str[]").unwrap();
+        let b = types.array(Type::STR);
+        assert_eq!(a, b);
+        assert_ne!(a, types.resolve("i32[]").unwrap());
+        assert_eq!(types.elem(a), Some(Type::STR));
+        assert_eq!(types.name(a), "str[]");
+        assert_eq!(types.llvm_type(a), "%arr");
+        assert_eq!(types.llvm_type(Type::BOOL), "i1");
+        assert!(types.resolve("i64").is_err());
+    }
+
+    #[test]
+    fn pointers_and_structs(){
+        let mut types = Types::new();
+        let ptr = types.intern(TypeKind::Pointer(Type::I32));
+        assert_eq!(ptr, types.intern(TypeKind::Pointer(Type::I32)));
+        assert_eq!(types.name(ptr), "i32*");
+        assert_eq!(types.llvm_type(ptr), "ptr");
+        let point = types.intern(TypeKind::Struct { name: "Point".to_string(), fields: vec![Type::I32, Type::I32] });
+        assert_eq!(types.name(point), "Point");
+        assert_eq!(types.llvm_type(point), "%Point");
+    }
+}